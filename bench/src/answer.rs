@@ -0,0 +1,85 @@
+use std::fmt::{self, Display};
+
+// Puzzle answer normalized for comparison, so expectations don't need to
+// match the exact type a day happens to return.
+#[derive(Clone, Debug)]
+pub enum Answer {
+  Int(i128),
+  Float(f64),
+  Str(String),
+  MultiLine(Vec<String>),
+}
+
+/// Relative tolerance for comparing two floats.
+const FLOAT_TOLERANCE: f64 = 1e-9;
+
+impl Answer {
+  /// Normalizes a displayed answer. Surrounding whitespace is ignored, as is
+  /// trailing whitespace on each line of multi-line answers.
+  pub fn parse(s: &str) -> Answer {
+    let s = s.trim_matches(|c: char| c == '\n' || c == '\r');
+    if s.contains('\n') {
+      return Answer::MultiLine(s.lines().map(|l| l.trim_end().to_string()).collect());
+    }
+    let s = s.trim();
+    if let Ok(n) = s.parse() {
+      Answer::Int(n)
+    } else if let Some(x) = s.parse().ok().filter(|x: &f64| x.is_finite()) {
+      Answer::Float(x)
+    } else {
+      Answer::Str(s.to_string())
+    }
+  }
+
+  pub fn of(x: &dyn Display) -> Answer { Answer::parse(&x.to_string()) }
+
+  pub fn matches(&self, other: &Answer) -> bool {
+    use Answer::*;
+    fn close(a: f64, b: f64) -> bool {
+      (a - b).abs() <= FLOAT_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+    }
+    match (self, other) {
+      (Int(a), Int(b)) => a == b,
+      (Float(a), Float(b)) => close(*a, *b),
+      // Exact, a float only equals an integer it represents exactly.
+      (Int(a), Float(b)) | (Float(b), Int(a)) => {
+        b.fract() == 0.0 && b.abs() < 2f64.powi(127) && *b as i128 == *a
+      }
+      (Str(a), Str(b)) => a == b,
+      (MultiLine(a), MultiLine(b)) => a == b,
+      _ => false,
+    }
+  }
+}
+
+impl Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Answer::Int(n) => write!(f, "{}", n),
+      Answer::Float(x) => write!(f, "{}", x),
+      Answer::Str(s) => write!(f, "{}", s),
+      Answer::MultiLine(lines) => write!(f, "\n{}", lines.join("\n")),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn int_float() {
+    let int = Answer::Int(256997859093114);
+    assert!(int.matches(&Answer::parse("256997859093114.0")));
+    assert!(!int.matches(&Answer::parse("256997859093115.0")));
+    assert!(!int.matches(&Answer::parse("256997859000000.0")));
+    assert!(!Answer::Int(3).matches(&Answer::Float(3.5)));
+    assert!(!Answer::Int(i128::MAX).matches(&Answer::Float(f64::MAX)));
+  }
+
+  #[test]
+  fn floats() {
+    assert!(Answer::Float(0.1 + 0.2).matches(&Answer::Float(0.3)));
+    assert!(!Answer::Float(1.0).matches(&Answer::Float(1.001)));
+  }
+}
@@ -0,0 +1,179 @@
+use std::any::Any;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use std::{panic, thread};
+
+use error::AocResult;
+
+use crate::{RunResult, Status};
+
+// Marks the start of the result a child writes to stdout, after whatever the
+// day printed itself. Fields are separated by NULs, which answers never hold.
+const MARKER: &str = "\0aoc result\0";
+
+/// Runs this executable again with `args` and decodes the result the child
+/// reports through `child_main`. The child is killed once it runs longer than
+/// `timeout`, and a crash without a result counts as a panic.
+pub fn in_child(args: &[&str], timeout: Duration) -> AocResult<RunResult> {
+  let mut child =
+    Command::new(std::env::current_exe()?).args(args).stdout(Stdio::piped()).spawn()?;
+  let mut stdout = child.stdout.take().expect("stdout is piped");
+  // Drained on the side so a chatty child can't fill the pipe and stall.
+  let reader = thread::spawn(move || {
+    let mut out = vec![];
+    let _ = stdout.read_to_end(&mut out);
+    out
+  });
+
+  let start = Instant::now();
+  let exit = loop {
+    if let Some(exit) = child.try_wait()? {
+      break exit;
+    }
+    if start.elapsed() > timeout {
+      child.kill()?;
+      child.wait()?;
+      let _ = reader.join();
+      return Ok(RunResult::failed(Status::Timeout));
+    }
+    thread::sleep(Duration::from_millis(5));
+  };
+
+  let out = reader.join().unwrap_or_default();
+  let out = String::from_utf8_lossy(&out);
+  let (printed, res) = match out.rfind(MARKER) {
+    Some(i) => (&out[..i], decode(&out[i + MARKER.len()..])),
+    None => (&out[..], None),
+  };
+  print!("{}", printed);
+  Ok(res.unwrap_or_else(|| RunResult::failed(Status::Panic(format!("child {}", exit)))))
+}
+
+/// Entry point of a child started by `in_child`: runs `f` and reports its
+/// result, error or panic message on stdout.
+pub fn child_main(f: impl FnOnce() -> AocResult<RunResult>) {
+  panic::set_hook(Box::new(|info| {
+    report(&RunResult::failed(Status::Panic(panic_message(info.payload()))));
+  }));
+  report(&RunResult::or_failed(f()));
+}
+
+/// Runs `f` on its own thread in this process, giving up on it after
+/// `timeout`. Unlike `in_child` a late `f` keeps running in the background,
+/// so this suits days that are merely slow rather than hung.
+pub fn with_timeout(
+  timeout: Duration,
+  f: impl FnOnce() -> AocResult<RunResult> + Send + 'static,
+) -> RunResult {
+  let (tx, rx) = mpsc::channel();
+  thread::spawn(move || {
+    let res = match panic::catch_unwind(panic::AssertUnwindSafe(f)) {
+      Ok(res) => RunResult::or_failed(res),
+      Err(payload) => RunResult::failed(Status::Panic(panic_message(&*payload))),
+    };
+    let _ = tx.send(res);
+  });
+  rx.recv_timeout(timeout).unwrap_or_else(|_| RunResult::failed(Status::Timeout))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+  match payload.downcast_ref::<String>() {
+    Some(msg) => msg.clone(),
+    None => payload.downcast_ref::<&str>().map_or("", |s| s).to_string(),
+  }
+}
+
+fn report(res: &RunResult) {
+  let mut out = std::io::stdout().lock();
+  let _ = write!(out, "{}{}", MARKER, encode(res));
+  let _ = out.flush();
+}
+
+fn encode(res: &RunResult) -> String {
+  let times = |ts: &[Duration]| ts.iter().map(|t| t.as_nanos().to_string()).collect::<Vec<_>>();
+  let (status, msg) = match &res.status {
+    Status::Ok => ("ok", ""),
+    Status::Timeout => ("timeout", ""),
+    Status::Panic(msg) => ("panic", msg.as_str()),
+    Status::Error(msg) => ("error", msg.as_str()),
+  };
+  [
+    times(&res.parse_elapsed).join(","),
+    times(&res.run_elapsed).join(","),
+    res.part1_result.to_string(),
+    res.part2_result.to_string(),
+    status.to_string(),
+    msg.to_string(),
+  ]
+  .join("\0")
+}
+
+fn decode(s: &str) -> Option<RunResult> {
+  let times = |s: &str| -> Option<Vec<Duration>> {
+    s.split(',').map(|t| Some(Duration::from_nanos(t.parse().ok()?))).collect()
+  };
+  let fields: Vec<_> = s.split('\0').collect();
+  let [parse, run, part1, part2, status, msg] = fields[..] else {
+    return None;
+  };
+  let status = match status {
+    "ok" => Status::Ok,
+    "timeout" => Status::Timeout,
+    "panic" => Status::Panic(msg.to_string()),
+    "error" => Status::Error(msg.to_string()),
+    _ => return None,
+  };
+  Some(RunResult {
+    parse_elapsed: times(parse)?,
+    run_elapsed: times(run)?,
+    part1_result: Box::new(part1.to_string()),
+    part2_result: Box::new(part2.to_string()),
+    status,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn round_trip() {
+    let res = RunResult {
+      parse_elapsed: vec![Duration::from_nanos(3)],
+      run_elapsed: vec![Duration::from_micros(5), Duration::from_millis(7)],
+      part1_result: Box::new(42),
+      part2_result: Box::new("#..\n.#."),
+      status: Status::Error("bad input".to_string()),
+    };
+    let got = decode(&encode(&res)).unwrap();
+    assert_eq!(got.parse_elapsed, res.parse_elapsed);
+    assert_eq!(got.run_elapsed, res.run_elapsed);
+    assert_eq!(got.part1_result.to_string(), "42");
+    assert_eq!(got.part2_result.to_string(), "#..\n.#.");
+    assert_eq!(got.status, res.status);
+  }
+
+  #[test]
+  fn thread_timeout() {
+    let ok = with_timeout(Duration::from_secs(10), || Ok(RunResult::failed(Status::Ok)));
+    assert_eq!(ok.status, Status::Ok);
+    let late = with_timeout(Duration::from_millis(10), || {
+      thread::sleep(Duration::from_secs(1));
+      Ok(RunResult::failed(Status::Ok))
+    });
+    assert_eq!(late.status, Status::Timeout);
+    let panicked = with_timeout(Duration::from_secs(10), || panic!("boom"));
+    assert_eq!(panicked.status, Status::Panic("boom".to_string()));
+    let failed = with_timeout(Duration::from_secs(10), || error::aoc_error("bad"));
+    assert!(matches!(failed.status, Status::Error(_)));
+  }
+
+  #[test]
+  fn malformed() {
+    assert!(decode("").is_none());
+    assert!(decode("1\x002\x00a\x00b\x00done\x00").is_none());
+    assert!(decode("x\x002\x00a\x00b\x00ok\x00").is_none());
+  }
+}
@@ -1,18 +1,134 @@
 // Rust macros are weird and fun
 
 use std::{time::Duration, fmt::Display};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use error::{aoc_error, AocResult};
+
+pub use inventory;
+
+mod answer;
+pub use answer::Answer;
+mod child;
+pub use child::{child_main, in_child, with_timeout};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+  Ok,
+  Timeout,
+  Panic(String),
+  Error(String),
+}
 
 pub struct RunResult {
   pub parse_elapsed: Vec<Duration>,
   pub run_elapsed: Vec<Duration>,
-  pub part1_result: Box<dyn Display>,
-  pub part2_result: Box<dyn Display>,
+  pub part1_result: Box<dyn Display + Send>,
+  pub part2_result: Box<dyn Display + Send>,
+  pub status: Status,
 }
 
 impl RunResult {
   pub fn avg_elapsed(&self) -> Duration {
     self.run_elapsed.iter().sum::<Duration>() / (self.run_elapsed.len() as u32)
   }
+
+  /// A day that didn't produce answers.
+  pub fn failed(status: Status) -> Self {
+    RunResult {
+      parse_elapsed: vec![Duration::ZERO],
+      run_elapsed: vec![Duration::ZERO],
+      part1_result: Box::new("-"),
+      part2_result: Box::new("-"),
+      status,
+    }
+  }
+
+  /// Turns an error into a failed result.
+  pub fn or_failed(res: AocResult<RunResult>) -> Self {
+    res.unwrap_or_else(|err| RunResult::failed(Status::Error(err.to_string())))
+  }
+}
+
+static DEFAULT_TIMEOUT_MS: AtomicU64 = AtomicU64::new(60_000);
+
+/// Wall-clock limit for days that don't set their own `timeout`.
+pub fn set_default_timeout(timeout: Duration) {
+  let ms = timeout.as_millis().try_into().unwrap_or(u64::MAX);
+  DEFAULT_TIMEOUT_MS.store(ms, Ordering::Relaxed);
+}
+
+pub fn default_timeout() -> Duration {
+  Duration::from_millis(DEFAULT_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+// A day registered with `#[aoc::solution(day N: ...)]`.
+pub struct Solution {
+  pub day: u32,
+  /// Repetitions `bench` times.
+  pub runs: usize,
+  pub tags: &'static [&'static str],
+  /// Wall-clock limit from the attribute's `timeout`, if any.
+  pub timeout: Option<Duration>,
+  /// Parses the given input file and runs both parts once.
+  pub run: fn(&str) -> AocResult<RunResult>,
+  /// Times the day on its input as configured in the attribute, checking the
+  /// expected answers. Panics on a wrong answer, so run it with `in_child`.
+  pub bench: fn() -> AocResult<RunResult>,
+}
+
+inventory::collect!(Solution);
+
+impl Solution {
+  /// Wall-clock limit for the day, its own or the default.
+  pub fn timeout(&self) -> Duration { self.timeout.unwrap_or_else(default_timeout) }
+}
+
+/// All registered days, in order. Registering a day twice is an error.
+pub fn solutions() -> AocResult<Vec<&'static Solution>> {
+  let mut res: Vec<_> = inventory::iter::<Solution>.into_iter().collect();
+  res.sort_by_key(|s| s.day);
+  if let Some(w) = res.windows(2).find(|w| w[0].day == w[1].day) {
+    return aoc_error(&format!("Day {} is registered more than once", w[0].day));
+  }
+  Ok(res)
+}
+
+pub fn solution(day: u32) -> AocResult<Option<&'static Solution>> {
+  Ok(solutions()?.into_iter().find(|s| s.day == day))
+}
+
+pub struct PartResult {
+  pub parse_elapsed: Vec<Duration>,
+  pub run_elapsed: Vec<Duration>,
+  pub result: Box<dyn Display + Send>,
+}
+
+// One named way of solving a single part, registered with
+// `#[aoc::implementation(day = N, part = P, name = "...")]`.
+pub struct Implementation {
+  pub day: u32,
+  pub part: u32,
+  pub name: &'static str,
+  /// Parses the given input file and runs the part once.
+  pub run: fn(&str) -> AocResult<PartResult>,
+}
+
+inventory::collect!(Implementation);
+
+/// Implementations registered for a day, by part and name. Names must be
+/// unique per part.
+pub fn implementations(day: u32) -> AocResult<Vec<&'static Implementation>> {
+  let mut res: Vec<_> =
+    inventory::iter::<Implementation>.into_iter().filter(|i| i.day == day).collect();
+  res.sort_by_key(|i| (i.part, i.name));
+  if let Some(w) = res.windows(2).find(|w| (w[0].part, w[0].name) == (w[1].part, w[1].name)) {
+    return aoc_error(&format!(
+      "Day {} part {} has more than one implementation `{}`",
+      day, w[0].part, w[0].name
+    ));
+  }
+  Ok(res)
 }
 
 #[macro_export]
@@ -40,6 +156,7 @@ macro_rules! aoc_run_batch {
       run_elapsed,
       part1_result: Box::new(res1),
       part2_result: Box::new(res2),
+      status: bench::Status::Ok,
     }
   }};
 }
@@ -71,6 +188,7 @@ macro_rules! aoc_run {
       run_elapsed,
       part1_result: Box::new(res1),
       part2_result: Box::new(res2),
+      status: bench::Status::Ok,
     }
   }};
 }
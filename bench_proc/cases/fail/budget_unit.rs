@@ -0,0 +1,7 @@
+use bench_proc::aoc_bench_proc;
+use error::AocResult;
+
+fn main() -> AocResult<()> {
+  let _ = aoc_bench_proc!(day 1: 10 runs, budget 5);
+  Ok(())
+}
@@ -0,0 +1,8 @@
+use error::AocResult;
+
+pub fn parse_input(_: &str) -> AocResult<i32> { Ok(0) }
+
+#[bench_proc::implementation(day = 1, part = 3, name = "fast")]
+fn part3(input: &i32) -> AocResult<i32> { Ok(*input) }
+
+fn main() {}
@@ -0,0 +1,21 @@
+use bench::Answer;
+use bench_proc::aoc_bench_proc;
+use error::AocResult;
+
+fn main() -> AocResult<()> {
+  if false {
+    // Suffixes and float spellings are not needed anymore.
+    let _ = aoc_bench_proc!(day 21: 10 runs, expected 256997859093114 3952288690726);
+    let _ = aoc_bench_proc!(day 21: 10 runs, expected 256997859093114.0 3952288690726_i64);
+  }
+
+  let same = |a: &str, b: &dyn std::fmt::Display| Answer::parse(a).matches(&Answer::of(b));
+  assert!(same("256997859093114", &256997859093114.0_f64));
+  assert!(same("1.5", &1.5000000000001_f64));
+  assert!(!same("1.5", &1.6_f64));
+  assert!(same("-3", &-3_i64));
+  assert!(same(" VJSFHWGFT\n", &"VJSFHWGFT"));
+  assert!(same("\n#..#  \n.##.", &"\n#..#\n.##.\n"));
+  assert!(!same("12", &"twelve"));
+  Ok(())
+}
@@ -0,0 +1,24 @@
+use error::AocResult;
+
+mod a {
+  use super::*;
+
+  pub fn parse_input(_: &str) -> AocResult<i32> { Ok(1) }
+
+  #[bench_proc::solution(day 3)]
+  pub fn run(input: &i32) -> AocResult<(i32, i32)> { Ok((*input, *input)) }
+}
+
+mod b {
+  use super::*;
+
+  pub fn parse_input(_: &str) -> AocResult<i32> { Ok(2) }
+
+  #[bench_proc::solution(day 3)]
+  pub fn run(input: &i32) -> AocResult<(i32, i32)> { Ok((*input, *input)) }
+}
+
+fn main() {
+  assert!(bench::solutions().is_err());
+  assert!(bench::solution(3).is_err());
+}
@@ -0,0 +1,12 @@
+use bench_proc::aoc_bench_proc;
+use error::AocResult;
+
+fn main() -> AocResult<()> {
+  if false {
+    let _ = aoc_bench_proc!(day 1: 10 runs, expected 71471 211189);
+    let _ = aoc_bench_proc!(day 5: 10 runs, expected "VJSFHWGFT" "LCTQFBVZV");
+    let _ = aoc_bench_proc!(day 10: 10 runs, expected 13860 _);
+    let _ = aoc_bench_proc!(day 11: 10 runs, expected _ 19457438264_i64);
+  }
+  Ok(())
+}
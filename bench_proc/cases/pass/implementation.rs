@@ -0,0 +1,22 @@
+use error::AocResult;
+
+mod day {
+  use super::*;
+
+  pub fn parse_input(fname: &str) -> AocResult<Vec<u8>> { Ok(fname.as_bytes().to_vec()) }
+
+  #[bench_proc::implementation(day = 7, part = 1, name = "len")]
+  fn part1(input: &[u8]) -> AocResult<usize> { Ok(input.len()) }
+
+  #[bench_proc::implementation(day = 7, part = 1, name = "count")]
+  fn part1_count(input: &[u8]) -> AocResult<usize> { Ok(input.iter().count()) }
+}
+
+fn main() -> AocResult<()> {
+  let imps = bench::implementations(7)?;
+  assert_eq!(imps.iter().map(|i| (i.part, i.name)).collect::<Vec<_>>(), [(1, "count"), (1, "len")]);
+  for imp in imps {
+    assert_eq!((imp.run)("abc")?.result.to_string(), "3");
+  }
+  Ok(())
+}
@@ -0,0 +1,11 @@
+use bench_proc::aoc_bench_proc;
+use error::AocResult;
+
+fn main() -> AocResult<()> {
+  // Only type checked, there are no inputs to run on.
+  if false {
+    let _ = aoc_bench_proc!(day 1: 10 runs);
+    let _ = aoc_bench_proc!(day 2: 0 runs);
+  }
+  Ok(())
+}
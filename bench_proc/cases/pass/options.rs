@@ -0,0 +1,23 @@
+use bench_proc::aoc_bench_proc;
+use error::AocResult;
+
+fn main() -> AocResult<()> {
+  if false {
+    let _ = aoc_bench_proc!(
+      day 1: 10 runs,
+      input "inputs/01sample",
+      part1 only,
+      warmup 2,
+      budget 500ms,
+      expected 24000 _
+    );
+    let _ = aoc_bench_proc!(day 2: 10 runs, budget 5s);
+    let _ = aoc_bench_proc!(day 4: 10 runs, timeout 30s, budget 5s, tags(slow, simd));
+  }
+  let (_, res, tags) = aoc_bench_proc!(day 3: 10 runs, skip "no input");
+  assert_eq!(res.part2_result.to_string(), "no input");
+  assert!(tags.is_empty());
+  let (_, res, tags) = aoc_bench_proc!(day 3: 10 runs, skip "no input", timeout 1s, tags(slow));
+  assert_eq!((res.status, tags), (bench::Status::Ok, &["slow"][..]));
+  Ok(())
+}
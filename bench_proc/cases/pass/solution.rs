@@ -0,0 +1,26 @@
+use error::AocResult;
+
+mod day {
+  use super::*;
+
+  pub fn parse_input(fname: &str) -> AocResult<Vec<u8>> { Ok(fname.as_bytes().to_vec()) }
+
+  #[bench_proc::solution(day 7: 5 runs, expected 3 "abc", input "abc", tags(fast), timeout 2s)]
+  pub fn run(input: &[u8]) -> AocResult<(usize, String)> {
+    Ok((input.len(), String::from_utf8_lossy(input).into_owned()))
+  }
+}
+
+fn main() -> AocResult<()> {
+  let solution = bench::solution(7)?.expect("day 7 is registered");
+  let res = (solution.run)("abc")?;
+  assert_eq!(res.part1_result.to_string(), "3");
+  assert_eq!(res.part2_result.to_string(), "abc");
+
+  assert_eq!((solution.runs, solution.tags), (5, &["fast"][..]));
+  assert_eq!(solution.timeout(), std::time::Duration::from_secs(2));
+  let res = (solution.bench)()?;
+  assert_eq!(res.status, bench::Status::Ok);
+  assert_eq!(res.run_elapsed.len(), 5);
+  Ok(())
+}
@@ -2,120 +2,455 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse::{Parse, ParseStream}, Result, parse_macro_input};
 
+// Grammar:
+//   day N[: K runs] [, option]*
+// with options
+//   expected A B      answers to check, `_` skips a part
+//   input "path"      alternate input file
+//   part1 only        ignore part 2
+//   warmup N          untimed runs before timing
+//   budget 5s         stop repeating once this much time is spent (s, ms, us)
+//   timeout 30s       hard wall-clock limit, the day fails with TIMEOUT past it
+//   tags(a, b)        labels to pick days by with `aoc bench --tag`
+//   skip "reason"     don't run at all
 struct DaySpec {
   day: syn::LitInt,
   n_runs: syn::LitInt,
   p1: Option<syn::Lit>,
   p2: Option<syn::Lit>,
+  input: Option<syn::LitStr>,
+  part1_only: bool,
+  warmup: Option<syn::LitInt>,
+  budget_us: Option<u64>,
+  timeout_us: Option<u64>,
+  tags: Vec<syn::Ident>,
+  skip: Option<syn::LitStr>,
 }
 
 impl DaySpec {
   fn module_path(&self) -> syn::Path {
-    let p = format!("solutions::day{:02}", self.day.base10_parse::<i32>().unwrap());
-    let s: TokenStream = p.parse().unwrap();
-    let path: syn::Path = syn::parse(s).unwrap();
-    path
+    // Validated while parsing.
+    let day = self.day.base10_parse::<u32>().unwrap();
+    let module = syn::Ident::new(&format!("day{:02}", day), self.day.span());
+    syn::parse_quote!(solutions::#module)
+  }
+}
+
+/// Parses a `T`, replacing syn's generic message with `msg` at the same span.
+fn parse_or<T: Parse>(input: ParseStream, msg: &str) -> Result<T> {
+  input.parse::<T>().map_err(|e| syn::Error::new(e.span(), msg))
+}
+
+fn keyword(input: ParseStream, kw: &str, msg: &str) -> Result<syn::Ident> {
+  let ident = parse_or::<syn::Ident>(input, msg)?;
+  if ident != kw {
+    return Err(syn::Error::new(ident.span(), msg));
+  }
+  Ok(ident)
+}
+
+/// An expected answer, `_` for no check.
+fn parse_answer(input: ParseStream, msg: &str) -> Result<Option<syn::Lit>> {
+  if input.peek(syn::Token!(_)) {
+    input.parse::<syn::Token!(_)>()?;
+    return Ok(None);
+  }
+  let lit = parse_or::<syn::Lit>(input, msg)?;
+  answer(&lit)?;
+  Ok(Some(lit))
+}
+
+/// Builds the `bench::Answer` for an expected answer. Integer and float
+/// suffixes are ignored, the check doesn't care about the returned type.
+fn answer(lit: &syn::Lit) -> Result<proc_macro2::TokenStream> {
+  match lit {
+    syn::Lit::Int(n) => {
+      let n = proc_macro2::Literal::i128_unsuffixed(n.base10_parse()?);
+      Ok(quote!(::bench::Answer::Int(#n)))
+    }
+    syn::Lit::Float(x) => {
+      let x = proc_macro2::Literal::f64_unsuffixed(x.base10_parse()?);
+      Ok(quote!(::bench::Answer::Float(#x)))
+    }
+    syn::Lit::Str(s) => Ok(quote!(::bench::Answer::parse(#s))),
+    _ => Err(syn::Error::new(lit.span(), "expected an integer, float or string answer")),
+  }
+}
+
+/// A duration like `5s` in microseconds, for the option `name`.
+fn parse_duration(input: ParseStream, name: &str) -> Result<u64> {
+  let msg = format!("expected duration like `5s` after `{}`", name);
+  let lit = parse_or::<syn::LitInt>(input, &msg)?;
+  let scale = match lit.suffix() {
+    "s" => 1_000_000,
+    "ms" => 1_000,
+    "us" => 1,
+    _ => {
+      let msg = format!("{} needs a unit: `s`, `ms` or `us`", name);
+      return Err(syn::Error::new(lit.span(), msg));
+    }
+  };
+  match lit.base10_parse::<u64>()?.checked_mul(scale) {
+    Some(0) => Err(syn::Error::new(lit.span(), format!("{} must be positive", name))),
+    Some(us) => Ok(us),
+    None => Err(syn::Error::new(lit.span(), format!("{} too large", name))),
   }
 }
 
 impl Parse for DaySpec {
   fn parse(input: ParseStream) -> Result<Self> {
-    if input.parse::<syn::Ident>()? != "day" { panic!("aaa"); }
-    let day = input.parse::<syn::LitInt>()?;
-    input.parse::<syn::Token!(:)>()?;
-    let n_runs = input.parse::<syn::LitInt>()?;
-    if input.parse::<syn::Ident>()? != "runs" { panic!("aaa"); }
-    
-    let (p1, p2) = if let Ok(_) = input.parse::<syn::Token!(,)>() {
-      if input.parse::<syn::Ident>()? != "expected" { panic!("aaa"); }
-      (Some(input.parse::<syn::Lit>()?), Some(input.parse::<syn::Lit>()?))
+    keyword(input, "day", "expected `day`")?;
+    let day = parse_or::<syn::LitInt>(input, "expected day number after `day`")?;
+    match day.base10_parse::<u32>() {
+      Ok(1..=25) => {}
+      _ => return Err(syn::Error::new(day.span(), "day must be a number from 1 to 25")),
+    }
+    let n_runs = if input.is_empty() || input.peek(syn::Token!(,)) {
+      syn::LitInt::new("1", day.span())
     } else {
-      (None, None)
+      parse_or::<syn::Token!(:)>(input, "expected `:` after day number")?;
+      let n_runs = parse_or::<syn::LitInt>(input, "expected run count after `:`")?;
+      n_runs.base10_parse::<usize>()?;
+      keyword(input, "runs", "expected `runs` after run count")?;
+      n_runs
+    };
+
+    let mut spec = DaySpec {
+      day,
+      n_runs,
+      p1: None,
+      p2: None,
+      input: None,
+      part1_only: false,
+      warmup: None,
+      budget_us: None,
+      timeout_us: None,
+      tags: vec![],
+      skip: None,
     };
-    Ok(DaySpec { day, n_runs, p1, p2 })
+
+    let mut seen: Vec<String> = vec![];
+    while !input.is_empty() {
+      parse_or::<syn::Token!(,)>(input, "expected `,` before next option")?;
+      let option = parse_or::<syn::Ident>(
+        input,
+        "expected one of `expected`, `input`, `part1`, `warmup`, `budget`, `timeout`, `tags`, \
+         `skip`",
+      )?;
+      let name = option.to_string();
+      if seen.contains(&name) {
+        return Err(syn::Error::new(option.span(), format!("duplicate option `{}`", name)));
+      }
+      seen.push(name.clone());
+
+      match name.as_str() {
+        "expected" => {
+          spec.p1 = parse_answer(input, "expected part 1 answer or `_` after `expected`")?;
+          spec.p2 = parse_answer(input, "expected part 2 answer or `_` after part 1 answer")?;
+        }
+        "input" => {
+          spec.input = Some(parse_or(input, "expected input path string after `input`")?);
+        }
+        "part1" => {
+          keyword(input, "only", "expected `only` after `part1`")?;
+          spec.part1_only = true;
+        }
+        "warmup" => {
+          let n = parse_or::<syn::LitInt>(input, "expected run count after `warmup`")?;
+          n.base10_parse::<usize>()?;
+          spec.warmup = Some(n);
+        }
+        "budget" => spec.budget_us = Some(parse_duration(input, "budget")?),
+        "timeout" => spec.timeout_us = Some(parse_duration(input, "timeout")?),
+        "tags" => {
+          let content;
+          syn::parenthesized!(content in input);
+          let tags = content.parse_terminated(syn::Ident::parse, syn::Token!(,))?;
+          spec.tags = tags.into_iter().collect();
+        }
+        "skip" => spec.skip = Some(parse_or(input, "expected reason string after `skip`")?),
+        _ => {
+          return Err(syn::Error::new(
+            option.span(),
+            format!("unknown option `{}`", name),
+          ))
+        }
+      }
+    }
+
+    if let (true, Some(p2)) = (spec.part1_only, &spec.p2) {
+      return Err(syn::Error::new(p2.span(), "part 2 answer given for a `part1 only` day"));
+    }
+    Ok(spec)
   }
 }
 
-fn aoc_run(spec: &DaySpec) -> proc_macro2::TokenStream {
-  let path = spec.module_path();
+/// Expression timing the day with `parse` and `run`, evaluating to an
+/// `AocResult<bench::RunResult>`. A wrong answer panics.
+fn aoc_run(
+  spec: &DaySpec,
+  parse: proc_macro2::TokenStream,
+  run: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
   let day = &spec.day;
   let n_runs = &spec.n_runs;
 
-  let p1_check = spec.p1.as_ref().map(|p1| quote!(
-    if res1 != #p1 {
-      panic!("Wrong answer on day {} part 1. Expected {}, got {}",
-             #day, #p1, res1);
+  let check = |part: usize, expected: &Option<syn::Lit>, res: proc_macro2::TokenStream| {
+    // Validated while parsing.
+    let expected = answer(expected.as_ref()?).unwrap();
+    Some(quote!(
+      let expected = #expected;
+      let got = ::bench::Answer::of(&#res);
+      if !expected.matches(&got) {
+        panic!("Wrong answer on day {} part {}. Expected {}, got {}",
+               #day, #part, expected, got);
+      }
+    ))
+  };
+  let p1_check = check(1, &spec.p1, quote!(res1));
+  let p2_check = check(2, &spec.p2, quote!(res2));
+  let part2_result = if spec.part1_only {
+    quote!(Box::new("-"))
+  } else {
+    quote!(Box::new(res2))
+  };
+
+  let input_file = match &spec.input {
+    Some(path) => quote!(#path.to_string()),
+    None => quote!(format!("inputs/{}input", day)),
+  };
+  let warmup = spec.warmup.as_ref().map(|n| quote!(
+    for _ in 0usize..#n {
+      run_once()?;
     }
   ));
-  let p2_check = spec.p2.as_ref().map(|p2| quote!(
-    if res2 != #p2 {
-      panic!("Wrong answer on day {} part 2. Expected {}, got {}",
-             #day, #p2, res2);
-    }
+  let drop_warmup = spec.warmup.as_ref().map(|n| quote!(
+    parse_elapsed.drain(..#n);
+    run_elapsed.drain(..#n);
   ));
+  let out_of_time = match spec.budget_us {
+    Some(us) => quote!(start.elapsed() > std::time::Duration::from_micros(#us)),
+    None => quote!(false),
+  };
 
-  quote!({
+  quote!((|| -> ::error::AocResult<_> {
     let day = format!("{:02}", #day);
-    let input_file = format!("inputs/{}input", day);
+    let input_file = #input_file;
 
     let mut run_elapsed = vec![];
     let mut parse_elapsed = vec![];
 
-    let mut run_once = || -> AocResult<_> {
-      use #path as day;
+    let mut run_once = || -> ::error::AocResult<_> {
       let t = std::time::Instant::now();
-      let input = day::parse_input(&input_file)?;
+      let input = #parse(&input_file)?;
       parse_elapsed.push(t.elapsed());
       let t = std::time::Instant::now();
-      let (res1, res2) = day::run(&input)?;
+      let (res1, res2) = #run(&input)?;
       run_elapsed.push(t.elapsed());
       Ok((res1, res2))
     };
 
+    #warmup
+
+    let start = std::time::Instant::now();
     for _ in 1usize..#n_runs {
+      if #out_of_time {
+        break;
+      }
       run_once()?;
     }
     let (res1, res2) = run_once()?;
+    #drop_warmup
 
     #p1_check
     #p2_check
 
-    bench::RunResult {
+    Ok(::bench::RunResult {
       parse_elapsed,
       run_elapsed,
       part1_result: Box::new(res1),
-      part2_result: Box::new(res2),
-    }
-  })
+      part2_result: #part2_result,
+      status: ::bench::Status::Ok,
+    })
+  })())
+}
+
+fn skipped(spec: &DaySpec) -> bool { spec.n_runs.base10_digits() == "0" || spec.skip.is_some() }
+
+/// Like `aoc_run`, but a placeholder result for skipped days.
+fn bench_body(
+  spec: &DaySpec,
+  parse: proc_macro2::TokenStream,
+  run: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+  if !skipped(spec) {
+    return aoc_run(spec, parse, run);
+  }
+  let reason = match &spec.skip {
+    Some(reason) => quote!(#reason),
+    None => quote!("Skipped"),
+  };
+  quote!(::error::AocResult::Ok(::bench::RunResult {
+    parse_elapsed: vec![std::time::Duration::ZERO],
+    run_elapsed: vec![std::time::Duration::ZERO],
+    part1_result: Box::new("Day"),
+    part2_result: Box::new(#reason),
+    status: ::bench::Status::Ok,
+  }))
 }
 
+/// `Option<Duration>` expression for the `timeout` option.
+fn timeout(spec: &DaySpec) -> proc_macro2::TokenStream {
+  match spec.timeout_us {
+    Some(us) => quote!(Some(std::time::Duration::from_micros(#us))),
+    None => quote!(None),
+  }
+}
+
+/// Times a day in place, evaluating to `(day, result, tags)`. With a
+/// `timeout` the day runs on its own thread and is abandoned once it's late.
 #[proc_macro]
 pub fn aoc_bench_proc(stream: TokenStream) -> TokenStream {
   let spec = parse_macro_input!(stream as DaySpec);
   let day = &spec.day;
   let n_runs = &spec.n_runs;
+  let tags = spec.tags.iter().map(|t| t.to_string());
+  let path = spec.module_path();
+  let body = bench_body(&spec, quote!(#path::parse_input), quote!(#path::run));
+  let announce = (!skipped(&spec)).then(|| quote!(
+    println!("Timing day {} {} times...", day, #n_runs);
+  ));
+  let res = match spec.timeout_us {
+    Some(us) => quote!(
+      ::bench::with_timeout(std::time::Duration::from_micros(#us), move || #body)
+    ),
+    None => quote!(::bench::RunResult::or_failed(#body)),
+  };
 
-  if n_runs.base10_digits() == "0" {
-    quote!({
-      let day = format!("{:02}", #day);
-
-      (day, bench::RunResult { 
-        parse_elapsed: vec![std::time::Duration::ZERO],
-        run_elapsed: vec![std::time::Duration::ZERO],
-        part1_result: Box::new("Day"),
-        part2_result: Box::new("Skipped"),
-      })
-    }).into()
-  } else {
-    let run = aoc_run(&spec);
-    quote!({
-      let day = format!("{:02}", #day);
-      let input_file = format!("inputs/{}input", day);
+  quote!({
+    let day = format!("{:02}", #day);
+    #announce
+    let res = #res;
+    let tags: &'static [&'static str] = &[#(#tags),*];
 
-      println!("Timing day {} {} times...", day, #n_runs);
-      let res = #run;
+    (day, res, tags)
+  }).into()
+}
 
-      (day, res)
-    }).into()
+fn parse_day(input: ParseStream) -> Result<syn::LitInt> {
+  let lit: syn::LitInt = input.parse()?;
+  match lit.base10_parse::<u32>() {
+    Ok(1..=25) => Ok(lit),
+    _ => Err(syn::Error::new(lit.span(), "day must be a number from 1 to 25")),
   }
 }
+
+/// Registers the `run` function it is put on, together with the sibling
+/// `parse_input`, as the solution for a day. Takes the same arguments as
+/// `aoc_bench_proc!`, which `aoc bench` uses to time it.
+///
+///   #[aoc::solution(day 12: 1000 runs, expected 456 454)]
+///   pub fn run(input: &Grid<u8>) -> AocResult<(i32, i32)> { ... }
+#[proc_macro_attribute]
+pub fn solution(attr: TokenStream, item: TokenStream) -> TokenStream {
+  let spec = parse_macro_input!(attr as DaySpec);
+  let item = parse_macro_input!(item as syn::ItemFn);
+  let day = &spec.day;
+  let n_runs = &spec.n_runs;
+  let tags = spec.tags.iter().map(|t| t.to_string());
+  let timeout = timeout(&spec);
+  let run = &item.sig.ident;
+  let body = bench_body(&spec, quote!(parse_input), quote!(#run));
+
+  quote!(
+    #item
+
+    ::bench::inventory::submit! {
+      ::bench::Solution {
+        day: #day,
+        runs: #n_runs,
+        tags: &[#(#tags),*],
+        timeout: #timeout,
+        run: |fname| {
+          let t = std::time::Instant::now();
+          let input = parse_input(fname)?;
+          let parse_elapsed = t.elapsed();
+          let t = std::time::Instant::now();
+          let (res1, res2) = #run(&input)?;
+          Ok(::bench::RunResult {
+            parse_elapsed: vec![parse_elapsed],
+            run_elapsed: vec![t.elapsed()],
+            part1_result: Box::new(res1),
+            part2_result: Box::new(res2),
+            status: ::bench::Status::Ok,
+          })
+        },
+        bench: || #body,
+      }
+    }
+  ).into()
+}
+
+/// Registers a function solving a single part, together with the sibling
+/// `parse_input`, as a named implementation of that part.
+///
+///   #[aoc::implementation(day = 14, part = 2, name = "simulate")]
+///   fn part2(input: &[Vec<Point>]) -> AocResult<i32> { ... }
+#[proc_macro_attribute]
+pub fn implementation(attr: TokenStream, item: TokenStream) -> TokenStream {
+  let mut day = None;
+  let mut part = None;
+  let mut name = None;
+  let parser = syn::meta::parser(|meta| {
+    if meta.path.is_ident("day") {
+      day = Some(parse_day(meta.value()?)?);
+    } else if meta.path.is_ident("part") {
+      let lit: syn::LitInt = meta.value()?.parse()?;
+      match lit.base10_parse::<u32>() {
+        Ok(1..=2) => part = Some(lit),
+        _ => return Err(syn::Error::new(lit.span(), "part must be 1 or 2")),
+      }
+    } else if meta.path.is_ident("name") {
+      name = Some(meta.value()?.parse::<syn::LitStr>()?);
+    } else {
+      return Err(meta.error("expected `day = N`, `part = P` or `name = \"...\"`"));
+    }
+    Ok(())
+  });
+  parse_macro_input!(attr with parser);
+  let item = parse_macro_input!(item as syn::ItemFn);
+
+  let (Some(day), Some(part), Some(name)) = (day, part, name) else {
+    return syn::Error::new(
+      proc_macro2::Span::call_site(),
+      "expected `day = N, part = P, name = \"...\"`",
+    )
+    .to_compile_error()
+    .into();
+  };
+  let run = &item.sig.ident;
+
+  quote!(
+    #item
+
+    ::bench::inventory::submit! {
+      ::bench::Implementation {
+        day: #day,
+        part: #part,
+        name: #name,
+        run: |fname| {
+          let t = std::time::Instant::now();
+          let input = parse_input(fname)?;
+          let parse_elapsed = t.elapsed();
+          let t = std::time::Instant::now();
+          let res = #run(&input)?;
+          Ok(::bench::PartResult {
+            parse_elapsed: vec![parse_elapsed],
+            run_elapsed: vec![t.elapsed()],
+            result: Box::new(res),
+          })
+        },
+      }
+    }
+  ).into()
+}
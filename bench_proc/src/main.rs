@@ -1,3 +0,0 @@
-fn main() {
-  //bench_proc::aoc_bench_proc!(day  1: run solutions::day01 | 1000);
-}
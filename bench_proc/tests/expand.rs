@@ -0,0 +1,12 @@
+// Expansion tests for the macros in this crate: everything in cases/pass must
+// compile and run, everything in cases/fail must fail with the error in the
+// matching .stderr file.
+//
+// After changing an error message, regenerate the .stderr files with
+// TRYBUILD=overwrite cargo test -p bench_proc.
+#[test]
+fn expand() {
+  let t = trybuild::TestCases::new();
+  t.pass("cases/pass/*.rs");
+  t.compile_fail("cases/fail/*.rs");
+}
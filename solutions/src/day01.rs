@@ -1,43 +1,159 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+
 use bstr::ByteSlice;
+use itertools::process_results;
 
-use error::AocResult;
-use crate::utils::read_all_nums_from_bytes;
+use error::{aoc_error, AocResult};
+use crate::utils::num_from_bytes;
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<Vec<i32>>> {
+/// Calories carried by each elf.
+pub fn parse_input(fname: &str) -> AocResult<Vec<i32>> {
   let f = std::fs::read(fname)?;
-
-  f.split_str(b"\n\n")
-    .map(|group| read_all_nums_from_bytes(group))
-    .collect::<AocResult<Vec<Vec<i32>>>>()
+  group_sums(&f)
 }
 
-fn part1(input: &Vec<Vec<i32>>) -> AocResult<i32> {
-  let res = input.iter().map(|x| x.into_iter().sum()).max().unwrap();
+/// Sums blank line separated groups in a single pass, without keeping the
+/// individual items around.
+pub fn group_sums(bytes: &[u8]) -> AocResult<Vec<i32>> {
+  let mut res = vec![];
+  let mut sum = None;
+  for line in ByteSlice::lines(bytes) {
+    if line.is_empty() {
+      res.extend(sum.take());
+      continue;
+    }
+    let Some(s) = sum.unwrap_or(0i32).checked_add(num_from_bytes::<i32>(line)?) else {
+      return aoc_error("Calorie sum overflows");
+    };
+    sum = Some(s);
+  }
+  res.extend(sum);
   Ok(res)
 }
 
-fn top3(data: &Vec<Vec<i32>>) -> i32 {
-  let mut big = 0;
-  let mut medium = 0;
-  let mut small = 0;
+/// Group sums read line by line, for inputs too big to keep in memory.
+pub struct StreamingSums<R> {
+  reader: R,
+  line: Vec<u8>,
+  bytes_read: u64,
+}
+
+impl<R: BufRead> StreamingSums<R> {
+  pub fn new(reader: R) -> Self { StreamingSums { reader, line: vec![], bytes_read: 0 } }
+
+  pub fn bytes_read(&self) -> u64 { self.bytes_read }
+}
+
+impl<R: BufRead> Iterator for StreamingSums<R> {
+  type Item = AocResult<i32>;
 
-  for x in data {
-    let x = x.iter().sum::<i32>();
-    if x > big {
-      (small, medium, big) = (medium, big, x);
-    } else if x > medium {
-      (small, medium) = (medium, x);
-    } else if x > small {
-      small = x;
+  fn next(&mut self) -> Option<Self::Item> {
+    let mut sum = None;
+    loop {
+      self.line.clear();
+      let n = match self.reader.read_until(b'\n', &mut self.line) {
+        Ok(n) => n,
+        Err(e) => return Some(Err(e.into())),
+      };
+      self.bytes_read += n as u64;
+      let line = self.line.trim_end_with(|c| c == '\n' || c == '\r');
+      if line.is_empty() {
+        // Blank line or end of input.
+        if sum.is_some() || n == 0 {
+          return sum.map(Ok);
+        }
+        continue;
+      }
+      let calories = match num_from_bytes::<i32>(line) {
+        Ok(c) => c,
+        Err(e) => return Some(Err(e)),
+      };
+      let Some(s) = sum.unwrap_or(0i32).checked_add(calories) else {
+        return Some(aoc_error("Calorie sum overflows"));
+      };
+      sum = Some(s);
     }
   }
+}
 
-  small + medium + big
+/// Top `k` elves of a streamed input, along with the number of bytes read.
+pub fn stream_top_k(reader: impl BufRead, k: usize) -> AocResult<(Vec<(usize, i32)>, u64)> {
+  let mut sums = StreamingSums::new(reader);
+  let top = process_results(&mut sums, |it| top_k(it, k))?;
+  Ok((top, sums.bytes_read()))
 }
 
-fn part2(input: &Vec<Vec<i32>>) -> AocResult<i32> {
-  let res = top3(input);
-  Ok(res)
+/// The `k` largest values as `(index, value)`, largest first. Ties go to the
+/// lower index.
+pub fn top_k(values: impl IntoIterator<Item = i32>, k: usize) -> Vec<(usize, i32)> {
+  if k == 0 {
+    return vec![];
+  }
+  // Min-heap of the best so far.
+  let mut heap = BinaryHeap::with_capacity(k + 1);
+  for (i, x) in values.into_iter().enumerate() {
+    heap.push(Reverse((x, Reverse(i))));
+    if heap.len() > k {
+      heap.pop();
+    }
+  }
+  heap
+    .into_sorted_vec()
+    .into_iter()
+    .map(|Reverse((x, Reverse(i)))| (i, x))
+    .collect()
 }
 
-pub fn run(input: &Vec<Vec<i32>>) -> AocResult<(i32, i32)> { Ok((part1(input)?, part2(input)?)) }
+fn top_k_sum(input: &[i32], k: usize) -> AocResult<i32> {
+  let top = top_k(input.iter().copied(), k);
+  match top.iter().try_fold(0i32, |acc, &(_, x)| acc.checked_add(x)) {
+    Some(sum) => Ok(sum),
+    None => aoc_error("Calorie sum overflows"),
+  }
+}
+
+fn part1(input: &[i32]) -> AocResult<i32> { top_k_sum(input, 1) }
+
+fn part2(input: &[i32]) -> AocResult<i32> { top_k_sum(input, 3) }
+
+#[aoc::solution(day 1: 1000 runs, expected 71471 211189)]
+pub fn run(input: &[i32]) -> AocResult<(i32, i32)> { Ok((part1(input)?, part2(input)?)) }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE: &[u8] = b"1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+
+  #[test]
+  fn sample() {
+    let sums = group_sums(SAMPLE).unwrap();
+    assert_eq!(sums, [6000, 4000, 11000, 24000, 10000]);
+    assert_eq!(run(&sums).unwrap(), (24000, 45000));
+    let streamed = StreamingSums::new(SAMPLE).collect::<AocResult<Vec<_>>>().unwrap();
+    assert_eq!(streamed, sums);
+    let (top, bytes_read) = stream_top_k(SAMPLE, 2).unwrap();
+    assert_eq!((top, bytes_read), (vec![(3, 24000), (2, 11000)], SAMPLE.len() as u64));
+  }
+
+  #[test]
+  fn overflow() {
+    let big = format!("{}\n1\n", i32::MAX);
+    assert!(group_sums(big.as_bytes()).is_err());
+    assert!(StreamingSums::new(big.as_bytes()).next().unwrap().is_err());
+    assert!(group_sums(b"2147483648\n").is_err());
+    let max = format!("{}\n\n1\n", i32::MAX);
+    assert_eq!(group_sums(max.as_bytes()).unwrap(), [i32::MAX, 1]);
+    assert!(part2(&[i32::MAX, 1]).is_err());
+  }
+
+  #[test]
+  fn top_ties_and_short_input() {
+    assert_eq!(top_k([3, 5, 5, 1], 2), [(1, 5), (2, 5)]);
+    assert_eq!(top_k([3], 3), [(0, 3)]);
+    assert!(top_k([3], 0).is_empty());
+    assert_eq!(group_sums(b"\n\n5\n\n\n6").unwrap(), [5, 6]);
+  }
+}
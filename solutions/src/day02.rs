@@ -1,17 +1,23 @@
-use itertools::Itertools;
+use bstr::ByteSlice;
 
-use error::AocResult;
+use error::{aoc_error, AocResult};
 
+/// Rounds as (opponent, response), both in 0..3.
 pub fn parse_input(fname: &str) -> AocResult<Vec<(i8, i8)>> {
   let s = std::fs::read(fname)?;
-  let res = s
-    .chunks_exact(4)
-    .map(|b| {
-      let (x, y) = (b[0] - b'A', b[2] - b'X');
-      (x as i8, y as i8)
+  parse_rounds(&s)
+}
+
+/// Parses `A X` lines, tolerating CRLF endings and a missing final newline.
+pub fn parse_rounds(s: &[u8]) -> AocResult<Vec<(i8, i8)>> {
+  s.lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty())
+    .map(|(i, line)| match *line.trim_end() {
+      [x @ b'A'..=b'C', b' ', y @ b'X'..=b'Z'] => Ok(((x - b'A') as i8, (y - b'X') as i8)),
+      _ => aoc_error(&format!("Invalid round on line {}: {:?}", i + 1, line.as_bstr())),
     })
-    .collect_vec();
-  Ok(res)
+    .collect()
 }
 
 // Lol, just look things up.
@@ -28,59 +34,88 @@ const P2: [[i8; 3]; 3] = [
   [2 + 0, 3 + 3, 1 + 6],
 ];
 
-fn part1(input: &Vec<(i8, i8)>) -> AocResult<i64> {
-  Ok(
-    input
-      .iter()
-      .map(|&(other, me)| unsafe {
-        *P1.get_unchecked(other as usize).get_unchecked(me as usize) as i64
-      })
-      .sum(),
-  )
+#[derive(Clone, Copy, Debug)]
+pub enum Scoring {
+  Lookup,
+  Math,
+}
+
+/// Score of a round where me is 0 rock, 1 paper, 2 scissors and outcome is
+/// 0 draw, 1 win, 2 loss.
+fn score(me: i8, outcome: i8) -> i64 { ((me + 1) + (outcome + 1) % 3 * 3) as i64 }
+
+fn score1(scoring: Scoring, other: i8, me: i8) -> i64 {
+  match scoring {
+    Scoring::Lookup => P1[other as usize][me as usize] as i64,
+    Scoring::Math => score(me, (me - other).rem_euclid(3)),
+  }
+}
+
+fn score2(scoring: Scoring, other: i8, given_outcome: i8) -> i64 {
+  match scoring {
+    Scoring::Lookup => P2[other as usize][given_outcome as usize] as i64,
+    Scoring::Math => {
+      let outcome = (given_outcome - 1).rem_euclid(3);
+      let me = (other + outcome).rem_euclid(3);
+      score(me, outcome)
+    }
+  }
+}
+
+fn part1(input: &[(i8, i8)], scoring: Scoring) -> AocResult<i64> {
+  Ok(input.iter().map(|&(other, me)| score1(scoring, other, me)).sum())
 }
 
-fn part2(input: &Vec<(i8, i8)>) -> AocResult<i64> {
-  Ok(
-    input
-      .iter()
-      .map(|&(other, me)| unsafe {
-        *P2.get_unchecked(other as usize).get_unchecked(me as usize) as i64
-      })
-      .sum(),
-  )
+fn part2(input: &[(i8, i8)], scoring: Scoring) -> AocResult<i64> {
+  Ok(input.iter().map(|&(other, outcome)| score2(scoring, other, outcome)).sum())
 }
 
-// Nice mathy way.
-//
-///// Assume me is 0 rock, 1 paper, 2 scissors
-///// and outcome is 0 draw, 1 win, 2 loss
-//fn score(me: i8, outcome: i8) -> i64 { ((me + 1) + (outcome + 1) % 3 * 3) as i64 }
-//
-//fn part1(input: &Vec<(i8, i8)>) -> AocResult<i64> {
-//  Ok(
-//    input
-//      .iter()
-//      .map(|&(other, me)| {
-//        let outcome = (me - other).rem_euclid(3);
-//        score(me, outcome)
-//      })
-//      .sum(),
-//  )
-//}
-//
-//fn part2(input: &Vec<(i8, i8)>) -> AocResult<i64> {
-//  Ok(
-//    input
-//      .iter()
-//      .map(|&(other, given_outcome)| {
-//        let outcome = (given_outcome - 1).rem_euclid(3);
-//        let me = (other + outcome).rem_euclid(3);
-//        score(me, outcome)
-//      })
-//      .sum(),
-//  )
-//}
-
-pub fn run(input: &Vec<(i8, i8)>) -> AocResult<(i64, i64)> {
-  Ok((part1(input)?, part2(input)?))
+#[aoc::implementation(day = 2, part = 1, name = "lookup")]
+fn part1_lookup(input: &[(i8, i8)]) -> AocResult<i64> { part1(input, Scoring::Lookup) }
+
+#[aoc::implementation(day = 2, part = 1, name = "math")]
+fn part1_math(input: &[(i8, i8)]) -> AocResult<i64> { part1(input, Scoring::Math) }
+
+#[aoc::implementation(day = 2, part = 2, name = "lookup")]
+fn part2_lookup(input: &[(i8, i8)]) -> AocResult<i64> { part2(input, Scoring::Lookup) }
+
+#[aoc::implementation(day = 2, part = 2, name = "math")]
+fn part2_math(input: &[(i8, i8)]) -> AocResult<i64> { part2(input, Scoring::Math) }
+
+#[aoc::solution(day 2: 1000 runs, expected 10310 14859)]
+pub fn run(input: &[(i8, i8)]) -> AocResult<(i64, i64)> {
+  Ok((part1(input, Scoring::Lookup)?, part2(input, Scoring::Lookup)?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn scorings_agree() {
+    for a in 0..3 {
+      for b in 0..3 {
+        assert_eq!(score1(Scoring::Lookup, a, b), score1(Scoring::Math, a, b), "{} {}", a, b);
+        assert_eq!(score2(Scoring::Lookup, a, b), score2(Scoring::Math, a, b), "{} {}", a, b);
+      }
+    }
+  }
+
+  #[test]
+  fn line_endings() {
+    let rounds = vec![(0, 1), (1, 0)];
+    assert_eq!(parse_rounds(b"A Y\nB X\n").unwrap(), rounds);
+    assert_eq!(parse_rounds(b"A Y\r\nB X\r\n").unwrap(), rounds);
+    assert_eq!(parse_rounds(b"A Y").unwrap(), [(0, 1)]);
+    assert_eq!(parse_rounds(b"A Y\nB X\n\n").unwrap(), rounds);
+    let rounds = parse_rounds(b"A Y\r\nB X\r\nC Z").unwrap();
+    assert_eq!(run(&rounds).unwrap(), (15, 12));
+  }
+
+  #[test]
+  fn invalid_rounds() {
+    for input in [&b"D X"[..], b"A W", b"AY", b"A  Y", b"a y", b"A Y\nB"] {
+      assert!(parse_rounds(input).is_err(), "{:?}", input.as_bstr());
+    }
+  }
 }
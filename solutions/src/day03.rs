@@ -1,10 +1,17 @@
+use std::simd::num::SimdUint;
+use std::simd::{u64x8, u8x8};
+
+use bstr::ByteSlice;
 use itertools::Itertools;
 
 use error::AocResult;
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<Vec<u8>>> {
-  let b = std::fs::read(fname)?;
-  Ok(b.split(|c| *c == b'\n').map(|bs| bs.to_vec()).collect_vec())
+/// The raw input, split into rucksacks on demand so parsing doesn't allocate
+/// per line.
+pub fn parse_input(fname: &str) -> AocResult<Vec<u8>> { Ok(std::fs::read(fname)?) }
+
+fn rucksacks(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+  input.lines().filter(|line| !line.is_empty())
 }
 
 fn priority(n: u8) -> u8 {
@@ -17,10 +24,11 @@ fn priority(n: u8) -> u8 {
   }
 }
 
-fn part1(input: &[Vec<u8>]) -> AocResult<i32> {
+#[aoc::implementation(day = 3, part = 1, name = "counts")]
+fn part1(input: &[u8]) -> AocResult<i32> {
   let mut counts = [0; 53];
   let mut res = 0;
-  for s in input {
+  for s in rucksacks(input) {
     counts.fill(0);
 
     let n = s.len();
@@ -38,10 +46,11 @@ fn part1(input: &[Vec<u8>]) -> AocResult<i32> {
   Ok(res)
 }
 
-fn part2(input: &[Vec<u8>]) -> AocResult<i32> {
+#[aoc::implementation(day = 3, part = 2, name = "counts")]
+fn part2(input: &[u8]) -> AocResult<i32> {
   let mut counts = [0u8; 53];
   let mut res = 0;
-  for (c1, c2, c3) in input.into_iter().tuples() {
+  for (c1, c2, c3) in rucksacks(input).tuples() {
     counts.fill(0);
 
     for c in c1 {
@@ -61,6 +70,104 @@ fn part2(input: &[Vec<u8>]) -> AocResult<i32> {
   Ok(res)
 }
 
-pub fn run(input: &[Vec<u8>]) -> AocResult<(i32, i32)> {
-  Ok((part1(input)?, part2(input)?))
+/// One bit per priority present.
+fn mask(s: &[u8]) -> u64 { s.iter().fold(0, |m, &c| m | 1 << priority(c)) }
+
+/// Same as `mask`, eight items at a time.
+fn simd_mask(s: &[u8]) -> u64 {
+  let chunks = s.chunks_exact(8);
+  let rest = mask(chunks.remainder());
+  let mut acc = u64x8::splat(0);
+  for chunk in chunks {
+    let c: u64x8 = u8x8::from_slice(chunk).cast();
+    // 26 extra for upper case, which has bit 5 clear.
+    let upper = (c >> 5 & u64x8::splat(1)) ^ u64x8::splat(1);
+    let p = (c & u64x8::splat(31)) + upper * u64x8::splat(26);
+    acc |= u64x8::splat(1) << p;
+  }
+  acc.reduce_or() | rest
+}
+
+fn solve1(input: &[u8], mask: fn(&[u8]) -> u64) -> i32 {
+  rucksacks(input)
+    .map(|s| {
+      let (a, b) = s.split_at(s.len() / 2);
+      // Priorities start at 1, so an empty intersection scores 0.
+      (mask(a) & mask(b)).trailing_zeros() as i32 % 64
+    })
+    .sum()
+}
+
+fn solve2(input: &[u8], mask: fn(&[u8]) -> u64) -> i32 {
+  rucksacks(input)
+    .tuples()
+    .map(|(a, b, c)| (mask(a) & mask(b) & mask(c)).trailing_zeros() as i32 % 64)
+    .sum()
+}
+
+#[aoc::implementation(day = 3, part = 1, name = "bitmask")]
+fn part1_bitmask(input: &[u8]) -> AocResult<i32> { Ok(solve1(input, mask)) }
+
+#[aoc::implementation(day = 3, part = 2, name = "bitmask")]
+fn part2_bitmask(input: &[u8]) -> AocResult<i32> { Ok(solve2(input, mask)) }
+
+#[aoc::implementation(day = 3, part = 1, name = "simd")]
+fn part1_simd(input: &[u8]) -> AocResult<i32> { Ok(solve1(input, simd_mask)) }
+
+#[aoc::implementation(day = 3, part = 2, name = "simd")]
+fn part2_simd(input: &[u8]) -> AocResult<i32> { Ok(solve2(input, simd_mask)) }
+
+#[aoc::solution(day 3: 1000 runs, expected 7831 2683)]
+pub fn run(input: &[u8]) -> AocResult<(i32, i32)> {
+  Ok((part1_bitmask(input)?, part2_bitmask(input)?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE: &[u8] = b"vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+  #[test]
+  fn sample() {
+    type Part = fn(&[u8]) -> AocResult<i32>;
+    let impls: [(Part, Part); 3] =
+      [(part1, part2), (part1_bitmask, part2_bitmask), (part1_simd, part2_simd)];
+    for (p1, p2) in impls {
+      assert_eq!((p1(SAMPLE).unwrap(), p2(SAMPLE).unwrap()), (157, 70));
+    }
+    assert_eq!(run(SAMPLE).unwrap(), (157, 70));
+  }
+
+  #[test]
+  fn priorities() {
+    assert_eq!((priority(b'a'), priority(b'z'), priority(b'A'), priority(b'Z')), (1, 26, 27, 52));
+  }
+
+  #[test]
+  fn simd_mask_matches_mask() {
+    let letters = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut x = 1u32;
+    for len in 0..=40 {
+      for _ in 0..20 {
+        let s = (0..len)
+          .map(|_| {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345);
+            letters[(x >> 16) as usize % letters.len()]
+          })
+          .collect::<Vec<_>>();
+        assert_eq!(simd_mask(&s), mask(&s), "{:?}", s.as_bstr());
+      }
+    }
+    // Every letter, in whole chunks and with a remainder.
+    assert_eq!(simd_mask(letters), mask(letters));
+    assert_eq!(simd_mask(&letters[..48]), mask(&letters[..48]));
+    assert_eq!(mask(letters), ((1u64 << 53) - 1) & !1);
+  }
 }
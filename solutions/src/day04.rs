@@ -1,33 +1,111 @@
-use itertools::Itertools;
+use bstr::ByteSlice;
+use num::PrimInt;
 
-use error::AocResult;
-use crate::utils;
+use error::{aoc_error, AocResult};
+use crate::utils::interval::IntervalSet;
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<[i8; 4]>> {
-  let ints = utils::read_all_nums(fname)?;
-  let res = ints.into_iter().array_chunks::<4>().collect_vec();
+pub fn parse_input(fname: &str) -> AocResult<Vec<[u32; 4]>> {
+  let b = std::fs::read(fname)?;
+  parse_pairs(&b)
+}
+
+/// Parses `a-b,c-d` lines into section ids of any integer width, failing on
+/// ids that don't fit rather than wrapping.
+pub fn parse_pairs<T: PrimInt>(bytes: &[u8]) -> AocResult<Vec<[T; 4]>> {
+  let mut res = vec![];
+  for line in bytes.lines().filter(|line| !line.is_empty()) {
+    let mut ids = [T::zero(); 4];
+    let mut fields = line.split(|&c| c == b'-' || c == b',');
+    for id in &mut ids {
+      let Some(field) = fields.next() else {
+        return aoc_error(&format!("Expected a-b,c-d, got {:?}", line.as_bstr()));
+      };
+      *id = match field.to_str().ok().map(|s| T::from_str_radix(s, 10)) {
+        Some(Ok(n)) => n,
+        _ => return aoc_error(&format!("Invalid section id {:?}", field.as_bstr())),
+      };
+    }
+    if fields.next().is_some() {
+      return aoc_error(&format!("Expected a-b,c-d, got {:?}", line.as_bstr()));
+    }
+    if ids[0] > ids[1] || ids[2] > ids[3] {
+      return aoc_error(&format!("Reversed range in {:?}", line.as_bstr()));
+    }
+    res.push(ids);
+  }
   Ok(res)
 }
 
-struct Range {
-  l: i8,
-  r: i8,
+/// Inclusive range of section ids.
+#[derive(Clone, Copy, Debug)]
+pub struct Range<T> {
+  pub l: T,
+  pub r: T,
 }
 
-impl Range {
-  fn new(l: i8, r: i8) -> Self { Range { l, r } }
-  fn intersects(&self, other: &Range) -> bool { !(self.r < other.l || other.r < self.l) }
-  fn contains(&self, other: &Range) -> bool {
+impl<T: PrimInt> Range<T> {
+  pub fn new(l: T, r: T) -> Self { Range { l, r } }
+  pub fn intersects(&self, other: &Range<T>) -> bool { !(self.r < other.l || other.r < self.l) }
+  pub fn contains(&self, other: &Range<T>) -> bool {
     (self.l <= other.l && other.r <= self.r) || (other.l <= self.l && self.r <= other.r)
   }
+
+  /// Number of sections in both ranges, failing when it doesn't fit in `T`.
+  pub fn overlap_len(&self, other: &Range<T>) -> AocResult<T> {
+    if !self.intersects(other) {
+      return Ok(T::zero());
+    }
+    let len = self.r.min(other.r).checked_sub(&self.l.max(other.l));
+    match len.and_then(|len| len.checked_add(&T::one())) {
+      Some(len) => Ok(len),
+      None => aoc_error("Overlap too large to count"),
+    }
+  }
+}
+
+pub fn pairs<T: PrimInt>(input: &[[T; 4]]) -> impl Iterator<Item = (Range<T>, Range<T>)> + '_ {
+  input.iter().map(|&[l1, r1, l2, r2]| (Range::new(l1, r1), Range::new(l2, r2)))
+}
+
+/// Indices of the pairs matching `pred`.
+pub fn matching<T: PrimInt>(
+  input: &[[T; 4]],
+  pred: impl Fn(&Range<T>, &Range<T>) -> bool,
+) -> Vec<usize> {
+  pairs(input).enumerate().filter(|(_, (a, b))| pred(a, b)).map(|(i, _)| i).collect()
+}
+
+pub fn contained_pairs<T: PrimInt>(input: &[[T; 4]]) -> Vec<usize> {
+  matching(input, |a, b| a.contains(b))
+}
+
+pub fn overlapping_pairs<T: PrimInt>(input: &[[T; 4]]) -> Vec<usize> {
+  matching(input, |a, b| a.intersects(b))
 }
 
-fn solve(input: &[[i8; 4]]) -> AocResult<(i32, i32)> {
+/// Sections shared by the two elves of each pair.
+pub fn overlap_lens<T: PrimInt>(input: &[[T; 4]]) -> AocResult<Vec<T>> {
+  pairs(input).map(|(a, b)| a.overlap_len(&b)).collect()
+}
+
+/// Number of distinct sections assigned to anyone.
+pub fn coverage<T: PrimInt>(input: &[[T; 4]]) -> AocResult<T> {
+  let mut sections = IntervalSet::new();
+  for (a, b) in pairs(input) {
+    for range in [a, b] {
+      let Some(end) = range.r.checked_add(&T::one()) else {
+        return aoc_error("Section id too large to cover");
+      };
+      sections.insert(range.l..end);
+    }
+  }
+  Ok(sections.len())
+}
+
+fn solve<T: PrimInt>(input: &[[T; 4]]) -> AocResult<(i32, i32)> {
   let mut res1 = 0;
   let mut res2 = 0;
-  for &[l1, r1, l2, r2] in input {
-    let r1 = Range::new(l1, r1);
-    let r2 = Range::new(l2, r2);
+  for (r1, r2) in pairs(input) {
     if r1.contains(&r2) {
       res1 += 1;
     }
@@ -38,4 +116,57 @@ fn solve(input: &[[i8; 4]]) -> AocResult<(i32, i32)> {
   Ok((res1, res2))
 }
 
-pub fn run(input: &[[i8; 4]]) -> AocResult<(i32, i32)> { Ok(solve(input)?) }
+#[aoc::solution(day 4: 1000 runs, expected 464 770)]
+pub fn run(input: &[[u32; 4]]) -> AocResult<(i32, i32)> { solve(input) }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE: &[u8] = b"2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n";
+
+  #[test]
+  fn pair_queries() {
+    let input = parse_pairs::<u8>(SAMPLE).unwrap();
+    assert_eq!(contained_pairs(&input), vec![3, 4]);
+    assert_eq!(overlapping_pairs(&input), vec![2, 3, 4, 5]);
+    assert_eq!(overlap_lens(&input).unwrap(), vec![0, 0, 1, 5, 1, 3]);
+    // Sections 2 to 9.
+    assert_eq!(coverage(&input).unwrap(), 8);
+    assert_eq!(solve(&input).unwrap(), (2, 4));
+  }
+
+  #[test]
+  fn touching_and_disjoint() {
+    let input = [[1u32, 1, 1, 1], [1, 2, 3, 4], [3, 4, 1, 3]];
+    assert_eq!(contained_pairs(&input), vec![0]);
+    assert_eq!(overlapping_pairs(&input), vec![0, 2]);
+    assert_eq!(overlap_lens(&input).unwrap(), vec![1, 0, 1]);
+    assert_eq!(coverage(&input).unwrap(), 4);
+  }
+
+  #[test]
+  fn id_limits() {
+    assert_eq!(parse_pairs::<u8>(b"0-255,255-255").unwrap(), vec![[0, 255, 255, 255]]);
+    assert!(parse_pairs::<u8>(b"0-256,1-2").is_err());
+    assert!(parse_pairs::<u8>(b"3-2,1-2").is_err());
+    assert!(parse_pairs::<u8>(b"1-2,3").is_err());
+    assert!(parse_pairs::<u8>(b"1-2,3-4-5").is_err());
+    // Covering 255 needs an end of 256.
+    assert!(coverage(&[[0u8, 255, 1, 2]]).is_err());
+    assert_eq!(coverage(&[[0u8, 254, 1, 2]]).unwrap(), 255);
+  }
+
+  #[test]
+  fn full_range_overlap() {
+    // 256 sections don't fit in a u8, 255 do.
+    assert!(overlap_lens(&[[0u8, 255, 0, 255]]).is_err());
+    assert_eq!(overlap_lens(&[[0u8, 255, 1, 255], [0, 254, 0, 255]]).unwrap(), [255, 255]);
+    assert!(overlap_lens(&[[-128i8, 127, -128, 127]]).is_err());
+    assert!(overlap_lens(&[[-100i8, 27, -128, 127]]).is_err());
+    assert_eq!(overlap_lens(&[[-100i8, 26, -128, 127]]).unwrap(), [127]);
+    // The other queries are fine with the full range.
+    let input = [[0u8, 255, 0, 255]];
+    assert_eq!((contained_pairs(&input), overlapping_pairs(&input)), (vec![0], vec![0]));
+  }
+}
@@ -1,78 +1,289 @@
+use std::io::Write;
+
 use bstr::ByteSlice;
 use itertools::Itertools;
 
-use error::AocResult;
+use error::{aoc_error, AocResult};
 use crate::utils::read_all_nums_from_bytes;
 
-type InputType = ([Vec<u8>; 9], Vec<(i8, i8, i8)>);
+/// Move `count` crates from stack `src` to `dst`, 1-based like the input.
+#[derive(Clone, Copy, Debug)]
+pub struct Move {
+  pub count: usize,
+  pub src: usize,
+  pub dst: usize,
+}
+
+type InputType = (Vec<Vec<u8>>, Vec<Move>);
 
 pub fn parse_input(fname: &str) -> AocResult<InputType> {
   let b = std::fs::read(fname)?;
-  let (crane, input) = b.split_once_str(b"\n\n").unwrap();
+  parse_stacks(&b)
+}
 
-  let mut piles: [Vec<_>; 9] = Default::default();
-  for s in crane.lines().rev().skip(1) {
-    for (i, &c) in s.into_iter().skip(1).step_by(4).enumerate() {
-      if c != b' ' {
-        piles[i].push(c);
+/// Parses the crate diagram and the moves below it.
+pub fn parse_stacks(b: &[u8]) -> AocResult<InputType> {
+  let mut lines = b.lines();
+  let diagram = lines.by_ref().take_while(|line| !line.trim().is_empty()).collect_vec();
+  let Some((labels, rows)) = diagram.split_last() else {
+    return aoc_error("Missing crate diagram");
+  };
+
+  // The label line decides how many stacks there are.
+  let labels = read_all_nums_from_bytes::<usize>(labels)?;
+  if labels.is_empty() || labels.iter().copied().ne(1..=labels.len()) {
+    return aoc_error("Stack labels should be 1, 2, 3, ...");
+  }
+  let mut piles = vec![vec![]; labels.len()];
+  for row in rows.iter().rev() {
+    for (i, &c) in row.iter().skip(1).step_by(4).enumerate() {
+      if c == b' ' {
+        continue;
       }
+      if i >= piles.len() || !c.is_ascii_alphabetic() {
+        return aoc_error(&format!("Invalid crate row {:?}", row.as_bstr()));
+      }
+      piles[i].push(c);
     }
   }
 
-  let instructions = read_all_nums_from_bytes(input)?
-    .into_iter()
-    .tuples()
-    .collect_vec();
+  let mut instructions = vec![];
+  for line in lines.filter(|line| !line.trim().is_empty()) {
+    let Some((count, src, dst)) = read_all_nums_from_bytes(line)?.into_iter().collect_tuple() else {
+      return aoc_error(&format!("Invalid move {:?}", line.as_bstr()));
+    };
+    if !(1..=piles.len()).contains(&src) || !(1..=piles.len()).contains(&dst) {
+      return aoc_error(&format!("Move refers to a missing stack: {:?}", line.as_bstr()));
+    }
+    instructions.push(Move { count, src, dst });
+  }
 
   Ok((piles, instructions))
 }
 
-fn part1(input: &InputType) -> AocResult<String> {
-  let (mut piles, instructions) = input.clone();
-  for (count, src, dst) in instructions {
-    let src = (src - 1) as usize;
-    let dst = (dst - 1) as usize;
-
-    unsafe {
-      assert_ne!(src, dst);
-      let src = piles.get_unchecked_mut(src) as *mut Vec<u8>;
-      let dst = piles.get_unchecked_mut(dst) as *mut Vec<u8>;
-      let n = (*src).len();
-      let it = (*src).drain(n - count as usize..);
-      (*dst).extend(it.rev());
-    }
+#[derive(Clone, Copy, Debug)]
+pub enum Crane {
+  /// Moves one crate at a time, reversing their order.
+  CrateMover9000,
+  /// Moves all crates at once.
+  CrateMover9001,
+}
+
+/// Mutable borrows of two different stacks.
+fn pair_mut(piles: &mut [Vec<u8>], a: usize, b: usize) -> (&mut Vec<u8>, &mut Vec<u8>) {
+  assert_ne!(a, b);
+  if a < b {
+    let (left, right) = piles.split_at_mut(b);
+    (&mut left[a], &mut right[0])
+  } else {
+    let (left, right) = piles.split_at_mut(a);
+    (&mut right[0], &mut left[b])
   }
+}
 
-  let bytes = piles
-    .into_iter()
-    .map(|pile| *pile.last().unwrap_or(&b'?'))
+fn apply(piles: &mut [Vec<u8>], step: usize, m: Move, crane: Crane) -> AocResult<()> {
+  let (src, dst) = (m.src - 1, m.dst - 1);
+  let held = piles[src].len();
+  if m.count > held {
+    return aoc_error(&format!(
+      "Move {}: can't take {} crates from stack {} holding {}",
+      step + 1, m.count, m.src, held
+    ));
+  }
+  // Moving onto the same stack leaves it as it was with either crane.
+  if src == dst {
+    return Ok(());
+  }
+  let (src, dst) = pair_mut(piles, src, dst);
+  let it = src.drain(held - m.count..);
+  match crane {
+    Crane::CrateMover9000 => dst.extend(it.rev()),
+    Crane::CrateMover9001 => dst.extend(it),
+  }
+  Ok(())
+}
+
+/// Runs all moves, calling `on_step` with each move and the stacks after it.
+pub fn simulate(
+  input: &InputType,
+  crane: Crane,
+  mut on_step: impl FnMut(usize, &Move, &[Vec<u8>]) -> AocResult<()>,
+) -> AocResult<Vec<Vec<u8>>> {
+  let (piles, instructions) = input;
+  let mut piles = piles.clone();
+  for (i, m) in instructions.iter().enumerate() {
+    apply(&mut piles, i, *m, crane)?;
+    on_step(i, m, &piles)?;
+  }
+  Ok(piles)
+}
+
+/// The stacks in the input's diagram format.
+pub fn render(piles: &[Vec<u8>]) -> String {
+  let height = piles.iter().map(|pile| pile.len()).max().unwrap_or(0);
+  let mut lines = (0..height)
+    .rev()
+    .map(|level| {
+      let row = piles
+        .iter()
+        .map(|pile| match pile.get(level) {
+          Some(&c) => format!("[{}]", c as char),
+          None => "   ".to_string(),
+        })
+        .join(" ");
+      row.trim_end().to_string()
+    })
     .collect_vec();
-  let s = String::from_utf8_lossy(&bytes).into_owned();
-  Ok(s)
+  // Labels end under the crate letters, also once they have two digits.
+  lines.push((1..=piles.len()).map(|i| format!("{:>2} ", i)).join(" ").trim_end().to_string());
+  lines.join("\n")
 }
 
-fn part2(input: &InputType) -> AocResult<String> {
-  let (mut piles, instructions) = input.clone();
-  for (count, src, dst) in instructions {
-    let src = (src - 1) as usize;
-    let dst = (dst - 1) as usize;
-
-    unsafe {
-      assert_ne!(src, dst);
-      let src = piles.get_unchecked_mut(src) as *mut Vec<u8>;
-      let dst = piles.get_unchecked_mut(dst) as *mut Vec<u8>;
-      let n = (*src).len();
-      let it = (*src).drain(n - count as usize..);
-      (*dst).extend(it);
+#[derive(Clone, Copy, Debug)]
+pub enum TraceFormat {
+  /// The diagram after each move.
+  Text,
+  /// One JSON object per move, with the stacks listed bottom to top.
+  Json,
+}
+
+fn trace_step(
+  out: &mut impl Write,
+  format: TraceFormat,
+  crane: Crane,
+  step: usize,
+  m: Option<&Move>,
+  piles: &[Vec<u8>],
+) -> AocResult<()> {
+  match format {
+    TraceFormat::Text => {
+      if let Some(m) = m {
+        writeln!(out, "move {} from {} to {}", m.count, m.src, m.dst)?;
+      }
+      writeln!(out, "{}\n", render(piles))?;
+    }
+    TraceFormat::Json => {
+      let m = match m {
+        Some(m) => format!(r#"{{"count":{},"from":{},"to":{}}}"#, m.count, m.src, m.dst),
+        None => "null".to_string(),
+      };
+      // Crates are ASCII letters, nothing to escape.
+      let stacks = piles.iter().map(|pile| format!("\"{}\"", pile.as_bstr())).join(",");
+      writeln!(
+        out,
+        r#"{{"crane":"{:?}","step":{},"move":{},"stacks":[{}]}}"#,
+        crane, step, m, stacks
+      )?;
     }
   }
+  Ok(())
+}
+
+/// Writes the stacks before the first move and after every move.
+pub fn trace(
+  input: &InputType,
+  crane: Crane,
+  format: TraceFormat,
+  out: &mut impl Write,
+) -> AocResult<()> {
+  trace_step(out, format, crane, 0, None, &input.0)?;
+  simulate(input, crane, |i, m, piles| trace_step(out, format, crane, i + 1, Some(m), piles))?;
+  Ok(())
+}
 
+fn tops(piles: &[Vec<u8>]) -> String {
   let bytes = piles
-    .into_iter()
+    .iter()
     .map(|pile| *pile.last().unwrap_or(&b'?'))
     .collect_vec();
-  let s = String::from_utf8_lossy(&bytes).into_owned();
-  Ok(s)
+  String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn part1(input: &InputType) -> AocResult<String> {
+  let piles = simulate(input, Crane::CrateMover9000, |_, _, _| Ok(()))?;
+  Ok(tops(&piles))
 }
 
+fn part2(input: &InputType) -> AocResult<String> {
+  let piles = simulate(input, Crane::CrateMover9001, |_, _, _| Ok(()))?;
+  Ok(tops(&piles))
+}
+
+#[aoc::solution(day 5: 1000 runs, expected "VJSFHWGFT" "LCTQFBVZV")]
 pub fn run(input: &InputType) -> AocResult<(String, String)> { Ok((part1(input)?, part2(input)?)) }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+  fn diagram(input: &str) -> &str { input.split("\n\n").next().unwrap() }
+
+  #[test]
+  fn sample() {
+    let input = parse_stacks(SAMPLE.as_bytes()).unwrap();
+    assert_eq!(input.0, [b"ZN".to_vec(), b"MCD".to_vec(), b"P".to_vec()]);
+    assert_eq!(input.1.len(), 4);
+    assert_eq!(run(&input).unwrap(), ("CMZ".to_string(), "MCD".to_string()));
+  }
+
+  #[test]
+  fn render_round_trip() {
+    let input = parse_stacks(SAMPLE.as_bytes()).unwrap();
+    assert_eq!(render(&input.0), diagram(SAMPLE));
+
+    let piles = simulate(&input, Crane::CrateMover9000, |_, _, _| Ok(())).unwrap();
+    assert_eq!(render(&piles), "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3");
+  }
+
+  #[test]
+  fn ten_stacks() {
+    let text = "\
+[A]                                     [J]
+[B] [C] [D] [E] [F] [G] [H] [I] [K] [L] [M]
+ 1   2   3   4   5   6   7   8   9  10  11
+
+move 2 from 11 to 10
+move 1 from 1 to 11
+";
+    let input = parse_stacks(text.as_bytes()).unwrap();
+    assert_eq!(input.0.len(), 11);
+    assert_eq!((input.0[9].as_slice(), input.0[10].as_slice()), (&b"L"[..], &b"MJ"[..]));
+    assert_eq!(render(&input.0), diagram(text));
+    let piles = simulate(&input, Crane::CrateMover9001, |_, _, _| Ok(())).unwrap();
+    assert_eq!(tops(&piles), "BCDEFGHIKJA");
+    // Crates past the last label are rejected.
+    assert!(parse_stacks(b"[A] [B]\n 1\n\nmove 1 from 1 to 1\n").is_err());
+  }
+
+  #[test]
+  fn over_draw() {
+    let input = parse_stacks(b"[A]\n 1   2\n\nmove 1 from 1 to 2\nmove 2 from 2 to 1\n").unwrap();
+    let Err(error::AocError::Custom(msg)) = part1(&input) else {
+      panic!("expected an error");
+    };
+    assert_eq!(msg, "Move 2: can't take 2 crates from stack 2 holding 1");
+    // Also when moving onto the same stack.
+    let input = parse_stacks(b"[A]\n 1\n\nmove 2 from 1 to 1\n").unwrap();
+    assert!(part2(&input).is_err());
+  }
+
+  #[test]
+  fn same_stack() {
+    let input = parse_stacks(b"[A]\n[B]\n 1   2\n\nmove 2 from 1 to 1\n").unwrap();
+    for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+      let piles = simulate(&input, crane, |_, _, _| Ok(())).unwrap();
+      assert_eq!(piles, input.0);
+    }
+  }
+}
@@ -1,60 +1,217 @@
-use error::AocResult;
+use error::{aoc_error, AocResult};
 
 pub fn parse_input(fname: &str) -> AocResult<Vec<u8>> {
   Ok(std::fs::read(fname)?)
 }
 
-struct DupCounter {
-  count: [u8; 256],
+/// Tracks whether the bytes in a sliding window are all distinct.
+pub trait Window {
+  fn add(&mut self, b: u8);
+  fn remove(&mut self, b: u8);
+  fn distinct(&self, n: usize) -> bool;
+}
+
+pub struct DupCounter {
+  count: [u16; 256],
   dupes: i32,
 }
 
-impl DupCounter {
-  fn new() -> Self{
-    DupCounter { count: [0; 256], dupes: 0 }
-  }
+impl Default for DupCounter {
+  fn default() -> Self { DupCounter { count: [0; 256], dupes: 0 } }
+}
+
+impl Window for DupCounter {
   fn add(&mut self, b: u8) {
-    self.count[b as usize] += 1; 
+    self.count[b as usize] += 1;
     if self.count[b as usize] == 2 {
       self.dupes += 1;
     }
   }
   fn remove(&mut self, b: u8) {
-    self.count[b as usize] -= 1; 
+    self.count[b as usize] -= 1;
     if self.count[b as usize] == 1 {
       self.dupes -= 1;
     }
   }
+  fn distinct(&self, _: usize) -> bool { self.dupes == 0 }
+}
+
+/// One bit per byte value, toggled on the way in and out. Duplicates cancel
+/// out, so the window is distinct exactly when all `n` bits are set.
+#[derive(Default)]
+pub struct XorMask {
+  mask: [u64; 4],
+  ones: usize,
+}
+
+impl Window for XorMask {
+  fn add(&mut self, b: u8) {
+    let word = &mut self.mask[b as usize / 64];
+    *word ^= 1 << (b % 64);
+    if *word >> (b % 64) & 1 == 1 {
+      self.ones += 1;
+    } else {
+      self.ones -= 1;
+    }
+  }
+  fn remove(&mut self, b: u8) { self.add(b) }
+  fn distinct(&self, n: usize) -> bool { self.ones == n }
 }
 
-fn solve(input: &[u8], n: usize) -> AocResult<i32> {
-  let mut counter = DupCounter::new();
+/// Finds markers, positions right after `n` distinct bytes, in a stream fed
+/// one chunk at a time.
+pub struct MarkerDetector<W = DupCounter> {
+  window: W,
+  ring: Box<[u8]>,
+  pos: usize,
+}
 
-  for i in 0..n {
-    counter.add(input[i]);
+impl<W: Window + Default> MarkerDetector<W> {
+  pub fn new(n: usize) -> AocResult<Self> {
+    if !(1..=256).contains(&n) {
+      return aoc_error(&format!("Marker length {} not in 1..=256", n));
+    }
+    Ok(MarkerDetector { window: W::default(), ring: vec![0; n].into(), pos: 0 })
   }
 
-  let mut res = n as i32;
-  for window in input.windows(n+1) {
-    counter.remove(window[0]);
-    counter.add(window[n]);
+  /// Bytes seen so far.
+  pub fn position(&self) -> usize { self.pos }
 
-    res += 1;
-    if counter.dupes == 0 {
-      break;
+  /// Calls `on_marker` with the position of every marker ending in `chunk`.
+  pub fn feed(&mut self, chunk: &[u8], mut on_marker: impl FnMut(usize)) {
+    let n = self.ring.len();
+    for &b in chunk {
+      let slot = &mut self.ring[self.pos % n];
+      if self.pos >= n {
+        self.window.remove(*slot);
+      }
+      *slot = b;
+      self.window.add(b);
+      self.pos += 1;
+      if self.pos >= n && self.window.distinct(n) {
+        on_marker(self.pos);
+      }
     }
   }
+}
+
+/// All marker positions in `input`.
+pub fn markers<W: Window + Default>(input: &[u8], n: usize) -> AocResult<Vec<usize>> {
+  let mut res = vec![];
+  MarkerDetector::<W>::new(n)?.feed(input, |pos| res.push(pos));
   Ok(res)
 }
 
+fn solve<W: Window + Default>(input: &[u8], n: usize) -> AocResult<i32> {
+  let mut detector = MarkerDetector::<W>::new(n)?;
+  let mut res = None;
+  // Stop at the first marker.
+  for chunk in input.chunks(64) {
+    detector.feed(chunk, |pos| {
+      res.get_or_insert(pos);
+    });
+    if let Some(res) = res {
+      return Ok(res as i32);
+    }
+  }
+  aoc_error(&format!("No marker of length {}", n))
+}
+
+#[aoc::implementation(day = 6, part = 1, name = "counts")]
 fn part1(input: &[u8]) -> AocResult<i32> {
-  solve(input, 4)
+  solve::<DupCounter>(input, 4)
 }
 
+#[aoc::implementation(day = 6, part = 2, name = "counts")]
 fn part2(input: &[u8]) -> AocResult<i32> {
-  solve(input, 14)
+  solve::<DupCounter>(input, 14)
 }
 
+#[aoc::implementation(day = 6, part = 1, name = "xor")]
+fn part1_xor(input: &[u8]) -> AocResult<i32> { solve::<XorMask>(input, 4) }
+
+#[aoc::implementation(day = 6, part = 2, name = "xor")]
+fn part2_xor(input: &[u8]) -> AocResult<i32> { solve::<XorMask>(input, 14) }
+
+#[aoc::solution(day 6: 1000 runs, expected 1578 2178)]
 pub fn run(input: &[u8]) -> AocResult<(i32, i32)> {
   Ok((part1(input)?, part2(input)?))
 }
+
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
+
+  use super::*;
+
+  fn brute(input: &[u8], n: usize) -> Vec<usize> {
+    (n..=input.len()).filter(|&end| input[end - n..end].iter().all_unique()).collect()
+  }
+
+  // Pseudo random bytes drawn from `0..alphabet`.
+  fn noise(len: usize, alphabet: u32) -> Vec<u8> {
+    let mut x = 12345u32;
+    (0..len)
+      .map(|_| {
+        x = x.wrapping_mul(1103515245).wrapping_add(12345);
+        ((x >> 16) % alphabet) as u8
+      })
+      .collect()
+  }
+
+  #[test]
+  fn samples() {
+    let samples = [
+      ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+      ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+      ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+      ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+      ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+    for (input, first4, first14) in samples {
+      let input = input.as_bytes();
+      assert_eq!((part1(input).unwrap(), part2(input).unwrap()), (first4, first14));
+      assert_eq!((part1_xor(input).unwrap(), part2_xor(input).unwrap()), (first4, first14));
+    }
+    assert!(part2(b"abcabcabcabcabcabc").is_err());
+  }
+
+  #[test]
+  fn windows_agree() {
+    for (alphabet, n) in [(4, 3), (8, 4), (16, 6), (26, 14), (256, 40)] {
+      let input = noise(3000, alphabet);
+      let expected = brute(&input, n);
+      assert!(!expected.is_empty(), "alphabet {}, n {}", alphabet, n);
+      assert_eq!(markers::<DupCounter>(&input, n).unwrap(), expected);
+      assert_eq!(markers::<XorMask>(&input, n).unwrap(), expected);
+    }
+  }
+
+  #[test]
+  fn chunked_feed() {
+    let input = noise(1000, 12);
+    let expected = brute(&input, 5);
+    for size in [1, 2, 3, 4, 5, 7, 64, 1000] {
+      let mut detector = MarkerDetector::<XorMask>::new(5).unwrap();
+      let mut found = vec![];
+      for chunk in input.chunks(size) {
+        detector.feed(chunk, |pos| found.push(pos));
+      }
+      assert_eq!(found, expected, "chunks of {}", size);
+      assert_eq!(detector.position(), input.len());
+    }
+  }
+
+  #[test]
+  fn lengths() {
+    // Every byte value once, twice over: each window of 256 is a marker.
+    let input = (0..512).map(|i| i as u8).collect::<Vec<_>>();
+    let expected = (256..=512).collect::<Vec<_>>();
+    assert_eq!(markers::<DupCounter>(&input, 256).unwrap(), expected);
+    assert_eq!(markers::<XorMask>(&input, 256).unwrap(), expected);
+    assert_eq!(markers::<XorMask>(&input[1..], 256).unwrap(), (256..=511).collect::<Vec<_>>());
+    assert_eq!(markers::<DupCounter>(b"aab", 1).unwrap(), [1, 2, 3]);
+    assert!(markers::<DupCounter>(&input, 0).is_err());
+    assert!(markers::<XorMask>(&input, 257).is_err());
+  }
+}
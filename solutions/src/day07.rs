@@ -1,60 +1,338 @@
+use std::collections::BTreeMap;
+
 use bstr::ByteSlice;
 use itertools::Itertools;
 
-use error::AocResult;
+use error::{aoc_error, AocResult};
+
+type NodeId = usize;
+
+const ROOT: NodeId = 0;
+
+enum Kind {
+  Dir(BTreeMap<String, NodeId>),
+  File,
+}
+
+struct Node {
+  name: String,
+  parent: NodeId,
+  kind: Kind,
+  /// Total size, for directories everything below them.
+  size: u64,
+}
+
+/// Directory tree rebuilt from a terminal transcript.
+pub struct FileSystem {
+  nodes: Vec<Node>,
+}
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<Vec<u8>>> {
+pub fn parse_input(fname: &str) -> AocResult<FileSystem> {
   let b = std::fs::read(fname)?;
-  let mut res = b.split(|c| *c == b'\n').map(|bs| bs.to_vec()).collect_vec();
-  if let Some(b) = res.last() {
-    if b.is_empty() {
-      res.pop();
+  FileSystem::from_transcript(&b)
+}
+
+impl FileSystem {
+  fn new() -> Self {
+    let root = Node {
+      name: "/".to_string(),
+      parent: ROOT,
+      kind: Kind::Dir(BTreeMap::new()),
+      size: 0,
+    };
+    FileSystem { nodes: vec![root] }
+  }
+
+  /// Replays `cd` and `ls` commands and their output. Listing a directory
+  /// again or revisiting it is fine as long as the entries agree.
+  pub fn from_transcript(bytes: &[u8]) -> AocResult<Self> {
+    let mut fs = FileSystem::new();
+    let mut cwd = ROOT;
+    let mut listing = false;
+    for (i, line) in bytes.lines().enumerate() {
+      let error =
+        |msg: &str| aoc_error(&format!("Line {}: {} in {:?}", i + 1, msg, line.as_bstr()));
+      let Ok(line) = line.to_str() else {
+        return error("not UTF-8");
+      };
+      let line = line.trim_end();
+      if line.is_empty() {
+        continue;
+      }
+      let words = line.split(' ').collect_vec();
+      match words[..] {
+        ["$", "ls"] => listing = true,
+        ["$", "cd", path] => {
+          listing = false;
+          cwd = match fs.resolve_dir(cwd, path) {
+            Ok(dir) => dir,
+            Err(msg) => return error(&msg),
+          };
+        }
+        ["$", ..] => return error("unknown command"),
+        _ if !listing => return error("output without `ls`"),
+        ["dir", name] => {
+          if let Err(msg) = fs.add(cwd, name, None) {
+            return error(&msg);
+          }
+        }
+        [size, name] => {
+          let Ok(size) = size.parse() else {
+            return error("invalid file size");
+          };
+          if let Err(msg) = fs.add(cwd, name, Some(size)) {
+            return error(&msg);
+          }
+        }
+        _ => return error("expected `dir NAME` or `SIZE NAME`"),
+      }
+    }
+    fs.compute_sizes();
+    Ok(fs)
+  }
+
+  fn children(&self, id: NodeId) -> Option<&BTreeMap<String, NodeId>> {
+    match &self.nodes[id].kind {
+      Kind::Dir(children) => Some(children),
+      Kind::File => None,
     }
   }
-  Ok(res)
+
+  /// Adds a directory, or a file when given a size, unless it's already there.
+  fn add(&mut self, dir: NodeId, name: &str, size: Option<u64>) -> Result<NodeId, String> {
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+      return Err(format!("invalid name `{}`", name));
+    }
+    if let Some(&id) = self.children(dir).and_then(|c| c.get(name)) {
+      return match (&self.nodes[id].kind, size) {
+        (Kind::Dir(_), None) => Ok(id),
+        (Kind::File, Some(size)) if self.nodes[id].size == size => Ok(id),
+        (Kind::File, Some(_)) => Err(format!("`{}` listed with a different size", name)),
+        _ => Err(format!("`{}` listed as both a file and a directory", name)),
+      };
+    }
+    let id = self.nodes.len();
+    let kind = if size.is_some() { Kind::File } else { Kind::Dir(BTreeMap::new()) };
+    self.nodes.push(Node { name: name.to_string(), parent: dir, kind, size: size.unwrap_or(0) });
+    let Kind::Dir(children) = &mut self.nodes[dir].kind else {
+      unreachable!("only directories are current directories");
+    };
+    children.insert(name.to_string(), id);
+    Ok(id)
+  }
+
+  /// The directory `cd path` ends up in. Directories that weren't listed
+  /// before are created, `cd` wouldn't have succeeded otherwise.
+  fn resolve_dir(&mut self, cwd: NodeId, path: &str) -> Result<NodeId, String> {
+    let mut dir = if path.starts_with('/') { ROOT } else { cwd };
+    for part in path.split('/').filter(|p| !p.is_empty() && *p != ".") {
+      dir = if part == ".." {
+        if dir == ROOT {
+          return Err("`cd ..` above /".to_string());
+        }
+        self.nodes[dir].parent
+      } else {
+        match self.children(dir).and_then(|c| c.get(part)) {
+          Some(&id) if self.children(id).is_none() => {
+            return Err(format!("`{}` is not a directory", part));
+          }
+          Some(&id) => id,
+          None => self.add(dir, part, None)?,
+        }
+      };
+    }
+    Ok(dir)
+  }
+
+  fn compute_sizes(&mut self) {
+    // Children always come after their parents.
+    for id in (1..self.nodes.len()).rev() {
+      let size = self.nodes[id].size;
+      let parent = self.nodes[id].parent;
+      self.nodes[parent].size += size;
+    }
+  }
+
+  fn path(&self, mut id: NodeId) -> String {
+    let mut parts = vec![];
+    while id != ROOT {
+      parts.push(self.nodes[id].name.as_str());
+      id = self.nodes[id].parent;
+    }
+    parts.reverse();
+    format!("/{}", parts.join("/"))
+  }
+
+  fn lookup(&self, path: &str) -> Option<NodeId> {
+    path
+      .split('/')
+      .filter(|p| !p.is_empty())
+      .try_fold(ROOT, |dir, part| self.children(dir)?.get(part).copied())
+  }
+
+  /// Size of the file or directory at the absolute `path`.
+  pub fn size(&self, path: &str) -> Option<u64> { self.lookup(path).map(|id| self.nodes[id].size) }
+
+  pub fn total_size(&self) -> u64 { self.nodes[ROOT].size }
+
+  /// Every directory with its size, in tree order.
+  pub fn dirs(&self) -> impl Iterator<Item = (String, u64)> + '_ {
+    let mut stack = vec![ROOT];
+    std::iter::from_fn(move || {
+      let id = stack.pop()?;
+      let children = self.children(id)?;
+      stack.extend(children.values().rev().filter(|&&c| self.children(c).is_some()));
+      Some((self.path(id), self.nodes[id].size))
+    })
+  }
+
+  /// Sizes of all directories, in no particular order.
+  pub fn dir_sizes(&self) -> impl Iterator<Item = u64> + '_ {
+    self.nodes.iter().filter(|node| matches!(node.kind, Kind::Dir(_))).map(|node| node.size)
+  }
+
+  /// The `k` largest directories, largest first.
+  pub fn largest_dirs(&self, k: usize) -> Vec<(String, u64)> {
+    self.dirs().sorted_by_key(|(_, size)| std::cmp::Reverse(*size)).take(k).collect()
+  }
+
+  /// The `k` smallest directories, smallest first.
+  pub fn smallest_dirs(&self, k: usize) -> Vec<(String, u64)> {
+    self.dirs().sorted_by_key(|(_, size)| *size).take(k).collect()
+  }
+
+  /// Listing in the style of the puzzle description.
+  pub fn tree(&self) -> String {
+    let mut out = String::new();
+    let mut stack = vec![(ROOT, 0)];
+    while let Some((id, depth)) = stack.pop() {
+      let node = &self.nodes[id];
+      let kind = if self.children(id).is_some() { "dir" } else { "file" };
+      out += &format!("{}- {} ({}, size={})\n", "  ".repeat(depth), node.name, kind, node.size);
+      if let Some(children) = self.children(id) {
+        stack.extend(children.values().rev().map(|&c| (c, depth + 1)));
+      }
+    }
+    out
+  }
 }
 
-fn solve(input: &[Vec<u8>]) -> AocResult<(i32, i32)> {
-  let mut stack = vec![];
-  let mut sizes = vec![];
-  let mut total = 0;
-  for line in input {
-    // This is hacky...
-    if line[0] == b'd' {
-      // dir *
-    } else if line[2] == b'l' {
-      // $ ls
-    } else if line.get(6) == Some(&b'.') {
-      // $ cd ..
-      let size = stack.pop().unwrap();
-      sizes.push(size);
-      *stack.last_mut().unwrap() += size;
-    } else if line[2] == b'c' {
-      // $ cd *
-      stack.push(0);
-    } else {
-      // 123 file.ext
-      let (size, _) = line.split_once_str(b" ").unwrap();
-      let size: i32 = crate::utils::num_from_bytes(size);
-      total += size;
-      *stack.last_mut().unwrap() += size;
-    }
-  }
-  while let Some(size) = stack.pop() {
-    sizes.push(size);
-    if let Some(last) = stack.last_mut() {
-      *last += size;
-    }
-  }
-
-  let res1 = sizes.iter().filter(|x| **x <= 100_000).sum();
-  let target = 30_000_000 - (70_000_000 - total);
-  let res2 = sizes
-    .into_iter()
-    .filter(|x| *x >= target)
-    .min()
-    .expect("no min?");
+fn solve(fs: &FileSystem) -> AocResult<(u64, u64)> {
+  let res1 = fs.dir_sizes().filter(|&size| size <= 100_000).sum();
+  let target = (30_000_000 + fs.total_size()).saturating_sub(70_000_000);
+  let Some(res2) = fs.dir_sizes().filter(|&size| size >= target).min() else {
+    return aoc_error("No directory big enough to free up space");
+  };
   Ok((res1, res2))
 }
 
-pub fn run(input: &[Vec<u8>]) -> AocResult<(i32, i32)> { solve(input) }
+#[aoc::solution(day 7: 1000 runs, expected 1543140 1117448)]
+pub fn run(input: &FileSystem) -> AocResult<(u64, u64)> { solve(input) }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+  fn parse(text: &str) -> AocResult<FileSystem> { FileSystem::from_transcript(text.as_bytes()) }
+
+  fn error(text: &str) -> String {
+    match parse(text) {
+      Err(error::AocError::Custom(msg)) => msg,
+      Err(e) => panic!("unexpected error {:?}", e),
+      Ok(_) => panic!("expected an error"),
+    }
+  }
+
+  #[test]
+  fn sample() {
+    let fs = parse(SAMPLE).unwrap();
+    assert_eq!(solve(&fs).unwrap(), (95437, 24933642));
+    assert_eq!(fs.size("/a/e"), Some(584));
+    assert_eq!(fs.size("/d/k"), Some(7214296));
+    assert_eq!(fs.size("/x"), None);
+    let dirs = fs.dirs().collect_vec();
+    assert_eq!(dirs.iter().map(|(path, _)| path.as_str()).collect_vec(), ["/", "/a", "/a/e", "/d"]);
+    assert_eq!(
+      fs.dir_sizes().sorted().collect_vec(),
+      dirs.iter().map(|&(_, size)| size).sorted().collect_vec()
+    );
+    assert_eq!(fs.largest_dirs(1), [("/".to_string(), 48381165)]);
+    assert_eq!(fs.smallest_dirs(2), [("/a/e".to_string(), 584), ("/a".to_string(), 94853)]);
+  }
+
+  #[test]
+  fn repeated_ls_and_revisits() {
+    // Listing /a twice and coming back to it later counts its files once.
+    let fs = parse(
+      "$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\n10 x\n$ ls\n10 x\n$ cd /\n$ cd a\n$ ls\n10 x\n",
+    )
+    .unwrap();
+    assert_eq!((fs.size("/a"), fs.total_size()), (Some(10), 10));
+    assert_eq!(fs.dir_sizes().collect_vec(), [10, 10]);
+  }
+
+  #[test]
+  fn absolute_cd() {
+    let fs = parse("$ cd /a/b\n$ ls\n5 x\n$ cd /a\n$ ls\n7 y\n$ cd b/../../c\n$ ls\n1 z\n")
+      .unwrap();
+    assert_eq!(fs.size("/a/b/x"), Some(5));
+    assert_eq!(fs.size("/a"), Some(12));
+    assert_eq!(fs.size("/c/z"), Some(1));
+    assert_eq!(fs.total_size(), 13);
+    assert_eq!(
+      fs.tree(),
+      "- / (dir, size=13)\n  - a (dir, size=12)\n    - b (dir, size=5)\n      \
+       - x (file, size=5)\n    - y (file, size=7)\n  - c (dir, size=1)\n    - z (file, size=1)\n"
+    );
+  }
+
+  #[test]
+  fn conflicting_entries() {
+    assert_eq!(
+      error("$ ls\n10 x\n$ ls\n11 x\n"),
+      "Line 4: `x` listed with a different size in \"11 x\""
+    );
+    assert_eq!(
+      error("$ ls\ndir x\n10 x\n"),
+      "Line 3: `x` listed as both a file and a directory in \"10 x\""
+    );
+    assert_eq!(error("$ ls\n10 x\n$ cd x\n"), "Line 3: `x` is not a directory in \"$ cd x\"");
+  }
+
+  #[test]
+  fn bad_transcripts() {
+    assert_eq!(error("$ cd /\n$ cd ..\n"), "Line 2: `cd ..` above / in \"$ cd ..\"");
+    assert_eq!(error("$ cd a/../..\n"), "Line 1: `cd ..` above / in \"$ cd a/../..\"");
+    assert_eq!(error("10 x\n"), "Line 1: output without `ls` in \"10 x\"");
+    assert_eq!(error("$ ls\n$ cd a\n10 x\n"), "Line 3: output without `ls` in \"10 x\"");
+    assert_eq!(error("$ rm x\n"), "Line 1: unknown command in \"$ rm x\"");
+    assert_eq!(error("$ ls\nten x\n"), "Line 2: invalid file size in \"ten x\"");
+    assert_eq!(error("$ ls\ndir ..\n"), "Line 2: invalid name `..` in \"dir ..\"");
+  }
+}
@@ -1,20 +1,17 @@
-use itertools::Itertools;
-
 use error::AocResult;
+use crate::utils::grid::Grid;
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<Vec<u8>>> {
+pub fn parse_input(fname: &str) -> AocResult<Grid<u8>> {
   let b = std::fs::read(fname)?;
-  let mut res = b.split(|c| *c == b'\n').map(|bs| bs.to_vec()).collect_vec();
-  assert!(res.pop().unwrap().is_empty());
-  Ok(res)
+  Grid::from_bytes(&b)
 }
 
-fn solve(input: &[Vec<u8>]) -> AocResult<(i32, i32)> {
-  let n = input.len();
-  let m = input[0].len();
+fn solve(input: &Grid<u8>) -> AocResult<(i32, i32)> {
+  let n = input.height();
+  let m = input.width();
 
-  let mut visible = vec![vec![false; m]; n];
-  let mut scenic = vec![vec![1; m]; n];
+  let mut visible = Grid::new(m, n, false);
+  let mut scenic = Grid::new(m, n, 1);
 
   macro_rules! sweep {
     ($it_i: expr, $it_j: expr, $i: ident, $j: ident, $ix_i: ident, $ix_j: ident) => {
@@ -24,12 +21,12 @@ fn solve(input: &[Vec<u8>]) -> AocResult<(i32, i32)> {
         let mut mx = -1; // Part 1
         let mut stack = vec![(start, inf)]; // Part 2
         for $j in $it_j.clone() {
-          let h = input[$ix_i][$ix_j];
+          let h = input[($ix_j, $ix_i)];
 
           // Part 1: Compare with max so far.
           if mx < h as i32 {
             mx = h as i32;
-            visible[$ix_i][$ix_j] = true;
+            visible[($ix_j, $ix_i)] = true;
           }
 
           // Part 2: Use monotonic stack to find the closest tree with
@@ -42,7 +39,7 @@ fn solve(input: &[Vec<u8>]) -> AocResult<(i32, i32)> {
             }
           }
           if let Some(&(ix, ph)) = stack.last() {
-            scenic[$ix_i][$ix_j] *= ($j as i32 - ix).abs();
+            scenic[($ix_j, $ix_i)] *= ($j as i32 - ix).abs();
             if ph == h {
               stack.pop();
             }
@@ -65,22 +62,12 @@ fn solve(input: &[Vec<u8>]) -> AocResult<(i32, i32)> {
   sweep!(j = 0..m, i = 0..n);
   sweep!(j = 0..m, i = (0..n).rev());
 
-  let res = visible
-    .into_iter()
-    .map(|v| {
-      v.into_iter()
-        .map(|vis| if vis { 1 } else { 0 })
-        .sum::<i32>()
-    })
-    .sum();
+  let res = visible.iter().filter(|&&vis| vis).count() as i32;
 
-  let res2 = scenic
-    .into_iter()
-    .map(|v| v.into_iter().max().unwrap())
-    .max()
-    .unwrap();
+  let res2 = *scenic.iter().max().unwrap();
 
   Ok((res, res2))
 }
 
-pub fn run(input: &[Vec<u8>]) -> AocResult<(i32, i32)> { solve(input) }
+#[aoc::solution(day 8: 1000 runs, expected 1538 496125)]
+pub fn run(input: &Grid<u8>) -> AocResult<(i32, i32)> { solve(input) }
@@ -1,37 +1,29 @@
 use std::hint::unreachable_unchecked;
 
 use bstr::ByteSlice;
-use itertools::Itertools;
 
 use error::AocResult;
+use crate::utils::point::Point2;
 
 type Input = (u8, i32);
 
 pub fn parse_input(fname: &str) -> AocResult<Vec<Input>> {
   let bs = std::fs::read(fname)?;
-  Ok(
-    bs.trim()
-      .split(|c| *c == b'\n')
-      .map(|b| {
-        let dir = b[0];
-        let count = if b[2] == b'-' {
-          -crate::utils::num_from_bytes::<i32>(&b[3..])
-        } else {
-          crate::utils::num_from_bytes(&b[2..])
-        };
-        (dir, count)
-      })
-      .collect_vec(),
-  )
+  bs.trim()
+    .split(|c| *c == b'\n')
+    .map(|b| {
+      let dir = b[0];
+      let count = if b[2] == b'-' {
+        -crate::utils::num_from_bytes::<i32>(&b[3..])?
+      } else {
+        crate::utils::num_from_bytes(&b[2..])?
+      };
+      Ok((dir, count))
+    })
+    .collect()
 }
 
-type PointInt = i32;
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-struct Pt {
-  x: PointInt,
-  y: PointInt,
-}
+type Pt = Point2<i32>;
 
 #[derive(Debug)]
 struct Snek<const N: usize> {
@@ -40,7 +32,7 @@ struct Snek<const N: usize> {
 }
 
 impl<const N: usize> Snek<N> {
-  fn new() -> Self { Snek { head: Pt{x:0, y:0}, tail: [Pt{x:0, y:0}; N] } }
+  fn new() -> Self { Snek { head: Pt::default(), tail: [Pt::default(); N] } }
   fn up(&mut self) { self.head.y -= 1; }
   fn down(&mut self) { self.head.y += 1; }
   fn left(&mut self) { self.head.x -= 1; }
@@ -51,13 +43,8 @@ impl<const N: usize> Snek<N> {
   fn follow(&mut self) {
     let mut last = self.head;
     for cur in &mut self.tail {
-      let dx = last.x - cur.x;
-      let dy = last.y - cur.y;
-      let distx = dx.abs();
-      let disty = dy.abs();
-      if distx.max(disty) >= 2 {
-        cur.x += dx.signum();
-        cur.y += dy.signum();
+      if last.chebyshev(*cur) >= 2 {
+        *cur += (last - *cur).signum();
       } else {
         break;
       }
@@ -78,7 +65,7 @@ impl Board {
   }
   fn insert(&mut self, p: Pt) {
     let n = self.n as i32;
-    let ix = (p.x as i32+n) + (p.y as i32+n)*(2*n + 1);
+    let ix = (p.x + n) + (p.y + n) * (2 * n + 1);
     if !self.grid[ix as usize] {
       self.uniq += 1;
       self.grid[ix as usize] = true;
@@ -116,4 +103,5 @@ fn part2(input: &[Input]) -> AocResult<i32> {
   solve::<9>(input)
 }
 
+#[aoc::solution(day 9: 1000 runs, expected 6339 2541)]
 pub fn run(input: &[Input]) -> AocResult<(i32, i32)> { Ok((part1(input)?, part2(input)?)) }
@@ -73,4 +73,5 @@ fn solve(input: &[Command]) -> AocResult<(i32, String)> {
   Ok((res, format!("\n{display}")))
 }
 
+#[aoc::solution(day 10: 1000 runs, expected 13860 _)]
 pub fn run(input: &[Command]) -> AocResult<(i32, String)> { solve(input) }
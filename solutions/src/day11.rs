@@ -1,7 +1,7 @@
 use ahash::{HashMap, RandomState};
 use itertools::Itertools;
 
-use error::AocResult;
+use error::{aoc_error, AocResult};
 
 #[derive(Debug)]
 struct Monkey<T> {
@@ -44,9 +44,11 @@ macro_rules! monkey {
   };
 }
 
+// Each monkey set is written once and handed to `$then!`, which turns it into
+// monkeys or into text to recognize the input file by.
 macro_rules! monkeys {
-  (input with $type: ty) => {
-    monkey!($type,
+  (input, $then: ident!($($args: tt)*)) => {
+    $then!($($args)*
       Monkey 0:
         Starting items: 53, 89, 62, 57, 74, 51, 83, 97
         Operation: new = old * 3
@@ -104,8 +106,8 @@ macro_rules! monkeys {
           If false: throw to monkey 1
     )
   };
-  (sample with $type: ty) => {
-    monkey!($type,
+  (sample, $then: ident!($($args: tt)*)) => {
+    $then!($($args)*
       Monkey 0:
         Starting items: 79, 98
         Operation: new = old * 19
@@ -137,10 +139,46 @@ macro_rules! monkeys {
   };
 }
 
-pub fn parse_input(_fname: &str) -> AocResult<()> { Ok(()) }
+// Both monkey sets are compiled in, the input file only selects one.
+#[derive(Clone, Copy, Debug)]
+pub enum Input {
+  Real,
+  Sample,
+}
+
+pub fn parse_input(fname: &str) -> AocResult<Input> {
+  let squashed = |s: &str| s.split_whitespace().collect::<String>();
+  let text = squashed(&std::fs::read_to_string(fname)?);
+  if text == squashed(monkeys!(input, stringify!())) {
+    Ok(Input::Real)
+  } else if text == squashed(monkeys!(sample, stringify!())) {
+    Ok(Input::Sample)
+  } else {
+    aoc_error("Only the compiled in monkeys are supported, see `monkeys!`")
+  }
+}
+
+trait Monkeys: Sized {
+  fn monkeys(input: Input) -> Vec<Monkey<Self>>;
+}
+
+macro_rules! impl_monkeys {
+  ($($type: ty),*) => {$(
+    impl Monkeys for $type {
+      fn monkeys(input: Input) -> Vec<Monkey<$type>> {
+        match input {
+          Input::Real => monkeys!(input, monkey!($type,)).into(),
+          Input::Sample => monkeys!(sample, monkey!($type,)).into(),
+        }
+      }
+    }
+  )*};
+}
+
+impl_monkeys!(i32, i64);
 
-fn part1(_: &()) -> AocResult<i32> {
-  let mut monkeys = monkeys!(input with i32);
+fn part1(input: &Input) -> AocResult<i32> {
+  let mut monkeys = i32::monkeys(*input);
 
   let mut counts = vec![0; monkeys.len()]; // Can't use array here with monkeys.len()?
 
@@ -168,45 +206,47 @@ fn part1(_: &()) -> AocResult<i32> {
   Ok(res)
 }
 
-//fn part2(_: &()) -> AocResult<i64> {
-//  let mut monkeys = monkeys!(input with i64);
-//
-//  let modulo: i64 = monkeys.iter().map(|m| m.div).unique().product();
-//
-//  let mut counts = vec![0i64; monkeys.len()]; // Can't use array here with monkeys.len()?
-//
-//  for monkey in &mut monkeys {
-//    monkey.items.reserve(40);
-//  }
-//
-//  for _ in 0..10000 {
-//    for i in 0..monkeys.len() {
-//      let (op, target, items) = {
-//        let monkey = &mut monkeys[i];
-//        (
-//          monkey.op,
-//          monkey.target,
-//          monkey.items.drain(..).collect_vec(),
-//        )
-//      };
-//      counts[i] += items.len() as i64;
-//      for item in items {
-//        let item = op(item);
-//        let item = item % modulo;
-//        let target = target(item);
-//        monkeys[target].items.push(item)
-//      }
-//    }
-//  }
-//
-//  let res = counts.into_iter().sorted().rev().take(2).product();
-//  Ok(res)
-//}
-
-fn part2(_: &()) -> AocResult<i64> {
+#[aoc::implementation(day = 11, part = 2, name = "brute")]
+fn part2_brute(input: &Input) -> AocResult<i64> {
+  let mut monkeys = i64::monkeys(*input);
+
+  let modulo: i64 = monkeys.iter().map(|m| m.div).unique().product();
+
+  let mut counts = vec![0i64; monkeys.len()]; // Can't use array here with monkeys.len()?
+
+  for monkey in &mut monkeys {
+    monkey.items.reserve(40);
+  }
+
+  for _ in 0..10000 {
+    for i in 0..monkeys.len() {
+      let (op, target, items) = {
+        let monkey = &mut monkeys[i];
+        (
+          monkey.op,
+          monkey.target,
+          monkey.items.drain(..).collect_vec(),
+        )
+      };
+      counts[i] += items.len() as i64;
+      for item in items {
+        let item = op(item);
+        let item = item % modulo;
+        let target = target(item);
+        monkeys[target].items.push(item)
+      }
+    }
+  }
+
+  let res = counts.into_iter().sorted().rev().take(2).product();
+  Ok(res)
+}
+
+#[aoc::implementation(day = 11, part = 2, name = "cycles")]
+fn part2(input: &Input) -> AocResult<i64> {
   let cycles = 10000;
 
-  let monkeys = monkeys!(input with i64);
+  let monkeys = i64::monkeys(*input);
 
   let modulo: i64 = monkeys.iter().map(|m| m.div).unique().product();
 
@@ -274,4 +314,5 @@ fn part2(_: &()) -> AocResult<i64> {
   Ok(res)
 }
 
-pub fn run(input: &()) -> AocResult<(i32, i64)> { Ok((part1(input)?, part2(input)?)) }
+#[aoc::solution(day 11: 1000 runs, expected 110220 19457438264)]
+pub fn run(input: &Input) -> AocResult<(i32, i64)> { Ok((part1(input)?, part2(input)?)) }
@@ -1,97 +1,72 @@
-use std::collections::VecDeque;
-
 use bstr::ByteSlice;
-use itertools::Itertools;
 
 use error::{aoc_error, AocResult};
+use crate::utils::grid::Grid;
+use crate::utils::point::Point2;
+use crate::utils::search::{self, Dense, NoParents};
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<Vec<u8>>> {
+pub fn parse_input(fname: &str) -> AocResult<Grid<u8>> {
   let b = std::fs::read(fname)?;
-  Ok(
-    b.trim()
-      .split(|c| *c == b'\n')
-      .map(|bs| bs.to_vec())
-      .collect_vec(),
-  )
-}
-
-#[derive(PartialOrd, Ord, PartialEq, Eq, Debug, Clone, Copy)]
-struct Pt {
-  x: i32,
-  y: i32,
+  Grid::from_bytes(b.trim())
 }
 
-impl Pt {
-  fn new(x: i32, y: i32) -> Self { Pt { x, y } }
-}
-
-fn bfs(input: &[Vec<u8>], starts: Vec<Pt>, target: Pt) -> AocResult<i32> {
-  let mut queue = VecDeque::new();
-  queue.extend(starts.into_iter().map(|pt| (0, pt, b'a')));
-
-  let mut visited = vec![vec![false; input[0].len()]; input.len()];
+type Pt = Point2<i32>;
 
-  while let Some(v) = queue.pop_front() {
-    let (dist, cur, h) = v;
-
-    for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-      let p = Pt::new(cur.x + dx, cur.y + dy);
-      // In range?
-      if let Some(new_h) = input
-        .get(p.y as usize)
-        .and_then(|row| row.get(p.x as usize))
-      {
-        // Valid height?
-        if h >= new_h - 1 {
-          // At target?
-          if p == target {
-            return Ok(dist + 1);
-          }
-          // Not previously visited?
-          if !visited[p.y as usize][p.x as usize] {
-            visited[p.y as usize][p.x as usize] = true;
-            queue.push_back((dist + 1, p, *new_h));
-          }
-        }
-      }
-    }
+fn climb(input: &Grid<u8>, starts: Vec<Pt>, target: Pt) -> AocResult<i32> {
+  let ix = |p: &Pt| (p.x as usize, p.y as usize);
+  let width = input.width();
+  let dist = Dense::new(width * input.height(), move |p: &Pt| p.y as usize * width + p.x as usize);
+  let res = search::bfs_in(
+    dist,
+    NoParents,
+    starts,
+    |&cur| {
+      let h = input[ix(&cur)];
+      input
+        .neighbors4(cur.x as usize, cur.y as usize)
+        .filter(move |&q| input[q] <= h + 1)
+        .map(|(x, y)| Pt::new(x as i32, y as i32))
+    },
+    |&p| p == target,
+  );
+  match res.goal_dist() {
+    Some(dist) => Ok(dist as i32),
+    None => aoc_error("No answer found"),
   }
-
-  aoc_error("No answer found")
 }
 
-fn part1(input: &[Vec<u8>], start: Pt, target: Pt) -> AocResult<i32> {
-  bfs(input, vec![start], target)
+fn part1(input: &Grid<u8>, start: Pt, target: Pt) -> AocResult<i32> {
+  climb(input, vec![start], target)
 }
 
-fn part2(input: &[Vec<u8>], starts: Vec<Pt>, target: Pt) -> AocResult<i32> {
-  bfs(input, starts, target)
+fn part2(input: &Grid<u8>, starts: Vec<Pt>, target: Pt) -> AocResult<i32> {
+  climb(input, starts, target)
 }
 
-fn prep_input(mut input: Vec<Vec<u8>>) -> (Vec<Vec<u8>>, Pt, Vec<Pt>, Pt) {
+fn prep_input(mut input: Grid<u8>) -> (Grid<u8>, Pt, Vec<Pt>, Pt) {
   let mut start = Pt::new(-1, -1);
   let mut target = Pt::new(-1, -1);
   let mut a_targets = vec![];
-  for (y, row) in input.iter_mut().enumerate() {
-    for (x, c) in row.iter_mut().enumerate() {
-      if *c == b'S' {
-        start = Pt::new(x as i32, y as i32);
-        *c = b'a';
-      }
-      if *c == b'E' {
-        target = Pt::new(x as i32, y as i32);
-        *c = b'z';
-      }
-      if *c == b'a' {
-        a_targets.push(Pt::new(x as i32, y as i32));
-      }
+  for (x, y) in input.positions() {
+    let c = &mut input[(x, y)];
+    if *c == b'S' {
+      start = Pt::new(x as i32, y as i32);
+      *c = b'a';
+    }
+    if *c == b'E' {
+      target = Pt::new(x as i32, y as i32);
+      *c = b'z';
+    }
+    if *c == b'a' {
+      a_targets.push(Pt::new(x as i32, y as i32));
     }
   }
   (input, start, a_targets, target)
 }
 
-pub fn run(input: &[Vec<u8>]) -> AocResult<(i32, i32)> {
-  let (input, start, a_starts, target) = prep_input(input.to_vec());
+#[aoc::solution(day 12: 1000 runs, expected 456 454)]
+pub fn run(input: &Grid<u8>) -> AocResult<(i32, i32)> {
+  let (input, start, a_starts, target) = prep_input(input.clone());
 
   Ok((
     part1(&input, start, target)?,
@@ -111,6 +111,7 @@ fn part2(input: &[Thing]) -> AocResult<usize> {
   Ok((i + 1) * (j + 1))
 }
 
+#[aoc::solution(day 13: 1000 runs, expected 6420 22000)]
 pub fn run(input: &[Thing]) -> AocResult<(usize, usize)> {
   Ok((part1(input)?, part2(input)?))
 }
@@ -1,41 +1,38 @@
 use itertools::Itertools;
 
 use error::AocResult;
+use crate::utils::grid::Grid;
+use crate::utils::point::Point2;
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<Vec<(i32, i32)>>> {
+type Point = Point2<i32>;
+
+pub fn parse_input(fname: &str) -> AocResult<Vec<Vec<Point>>> {
   let b = std::fs::read(fname)?;
   let asd = b
     .split(|c| *c == b'\n')
     .map(|bs| {
       let ints = crate::utils::read_all_nums_from_bytes(bs)?;
-      Ok(ints.into_iter().tuples().collect_vec())
+      Ok(ints.into_iter().tuples().map(|(x, y)| Point::new(x, y)).collect_vec())
     })
     .collect();
   asd
 }
 
+const WIDTH: usize = 1000;
 const HEIGHT: usize = 200;
 
-fn draw_grid(grid: &mut [[bool; 1000]; HEIGHT], input: &[Vec<(i32, i32)>]) -> usize {
+fn draw_grid(grid: &mut Grid<bool>, input: &[Vec<Point>]) -> usize {
   let mut max_y = 0;
   for shape in input {
-    for &[(x1, y1), (x2, y2)] in shape.array_windows() {
-      max_y = max_y.max(y1).max(y2);
-      if x1 == x2 {
-        // Lol, I guess this works
-        for y in y1..=y2 {
-          grid[y as usize][x1 as usize] = true;
-        }
-        for y in y2..=y1 {
-          grid[y as usize][x1 as usize] = true;
-        }
-      } else if y1 == y2 {
-        for x in x1..=x2 {
-          grid[y1 as usize][x as usize] = true;
-        }
-        for x in x2..=x1 {
-          grid[y1 as usize][x as usize] = true;
-        }
+    for &[from, to] in shape.array_windows() {
+      max_y = max_y.max(from.y).max(to.y);
+      // Lines are axis aligned, so this walks along them.
+      let step = (to - from).signum();
+      let mut p = from;
+      grid[(p.x as usize, p.y as usize)] = true;
+      while p != to {
+        p += step;
+        grid[(p.x as usize, p.y as usize)] = true;
       }
     }
   }
@@ -44,8 +41,8 @@ fn draw_grid(grid: &mut [[bool; 1000]; HEIGHT], input: &[Vec<(i32, i32)>]) -> us
   max_y
 }
 
-fn part1(input: &[Vec<(i32, i32)>]) -> AocResult<i32> {
-  let mut grid = [[false; 1000]; HEIGHT];
+fn part1(input: &[Vec<Point>]) -> AocResult<i32> {
+  let mut grid = Grid::new(WIDTH, HEIGHT, false);
   let max_y = draw_grid(&mut grid, input);
 
   let mut res = 0;
@@ -56,15 +53,15 @@ fn part1(input: &[Vec<(i32, i32)>]) -> AocResult<i32> {
       break;
     }
 
-    if !grid[y + 1][x] {
+    if !grid[(x, y + 1)] {
       stack.push((x, y + 1));
-    } else if !grid[y + 1][x - 1] {
+    } else if !grid[(x - 1, y + 1)] {
       stack.push((x - 1, y + 1));
-    } else if !grid[y + 1][x + 1] {
+    } else if !grid[(x + 1, y + 1)] {
       stack.push((x + 1, y + 1));
     } else {
       res += 1;
-      grid[y][x] = true;
+      grid[(x, y)] = true;
       stack.pop();
     }
   }
@@ -72,14 +69,12 @@ fn part1(input: &[Vec<(i32, i32)>]) -> AocResult<i32> {
   Ok(res)
 }
 
-#[allow(unused)]
-fn part2(input: &[Vec<(i32, i32)>]) -> AocResult<i32> {
-  let mut grid = [[false; 1000]; HEIGHT];
+#[aoc::implementation(day = 14, part = 2, name = "simulate")]
+fn part2(input: &[Vec<Point>]) -> AocResult<i32> {
+  let mut grid = Grid::new(WIDTH, HEIGHT, false);
   let max_y = draw_grid(&mut grid, input);
 
-  for x in 0..1000 {
-    grid[max_y][x] = true;
-  }
+  grid.row_mut(max_y).fill(true);
 
   let mut res = 0;
   let mut stack = vec![(500, 0)];
@@ -87,15 +82,15 @@ fn part2(input: &[Vec<(i32, i32)>]) -> AocResult<i32> {
   loop {
     let Some(&(x, y)) = stack.last() else { break; };
 
-    if !grid[y + 1][x] {
+    if !grid[(x, y + 1)] {
       stack.push((x, y + 1));
-    } else if !grid[y + 1][x - 1] {
+    } else if !grid[(x - 1, y + 1)] {
       stack.push((x - 1, y + 1));
-    } else if !grid[y + 1][x + 1] {
+    } else if !grid[(x + 1, y + 1)] {
       stack.push((x + 1, y + 1));
     } else {
       res += 1;
-      grid[y][x] = true;
+      grid[(x, y)] = true;
       stack.pop();
     }
   }
@@ -103,25 +98,26 @@ fn part2(input: &[Vec<(i32, i32)>]) -> AocResult<i32> {
   Ok(res)
 }
 
-fn part2_alt(input: &[Vec<(i32, i32)>]) -> AocResult<i32> {
-  let mut grid = [[false; 1000]; HEIGHT];
-  let mut grains = [[false; 1000]; HEIGHT];
+#[aoc::implementation(day = 14, part = 2, name = "rows")]
+fn part2_alt(input: &[Vec<Point>]) -> AocResult<i32> {
+  let mut grid = Grid::new(WIDTH, HEIGHT, false);
+  let mut grains = Grid::new(WIDTH, HEIGHT, false);
   let max_y = draw_grid(&mut grid, input);
 
   let mut min_x = 500;
   let mut max_x = 501;
 
   let mut res = 1;
-  grains[0][500] = true;
+  grains[(500, 0)] = true;
 
-  fn f(res: &mut i32, x: usize, y: usize, grains: &mut [[bool; 1000]; HEIGHT], grid: &[[bool; 1000]; HEIGHT]) -> bool {
-    if !grid[y][x] {
-      let a = grains[y-1][x-1];
-      let b = grains[y-1][x];
-      let c = grains[y-1][x+1];
+  fn f(res: &mut i32, x: usize, y: usize, grains: &mut Grid<bool>, grid: &Grid<bool>) -> bool {
+    if !grid[(x, y)] {
+      let a = grains[(x-1, y-1)];
+      let b = grains[(x, y-1)];
+      let c = grains[(x+1, y-1)];
       let to_fill = a || b || c;
       *res += to_fill as i32;
-      grains[y][x] = to_fill;
+      grains[(x, y)] = to_fill;
       to_fill
     } else {
       false
@@ -142,6 +138,7 @@ fn part2_alt(input: &[Vec<(i32, i32)>]) -> AocResult<i32> {
   Ok(res)
 }
 
-pub fn run(input: &[Vec<(i32, i32)>]) -> AocResult<(i32, i32)> {
+#[aoc::solution(day 14: 1000 runs, expected 838 27539)]
+pub fn run(input: &[Vec<Point>]) -> AocResult<(i32, i32)> {
   Ok((part1(input)?, part2_alt(input)?))
 }
@@ -1,20 +1,11 @@
 use itertools::{iproduct, Itertools};
 
-use error::AocResult;
+use error::{aoc_error, AocResult};
+use crate::utils::interval::IntervalSet;
+use crate::utils::point::Point2;
 use crate::utils::read_all_signed_nums;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct Point {
-  x: i32,
-  y: i32,
-}
-
-impl Point {
-  fn new(x: i32, y: i32) -> Self { Point { x, y } }
-  fn dist(&self, other: Self) -> u32 {
-    self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
-  }
-}
+type Point = Point2<i32>;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Circle {
@@ -24,7 +15,7 @@ pub struct Circle {
 
 impl Circle {
   fn new(sensor: Point, beacon: Point) -> Self {
-    Circle { center: sensor, rad: sensor.dist(beacon) }
+    Circle { center: sensor, rad: sensor.manhattan(beacon) as u32 }
   }
   fn x(&self) -> i32 { self.center.x }
   fn y(&self) -> i32 { self.center.y }
@@ -51,7 +42,7 @@ impl Circle {
 pub fn parse_input(fname: &str) -> AocResult<(Vec<Circle>, Vec<Point>)> {
   let mut circles = vec![];
   let mut beacons = vec![];
-  for &[sx, sy, bx, by] in  read_all_signed_nums(fname)?.array_chunks() {
+  for &[sx, sy, bx, by] in  read_all_signed_nums(fname)?.as_chunks().0 {
     let s = Point::new(sx, sy);
     let b = Point::new(bx, by);
     circles.push(Circle::new(s, b));
@@ -60,83 +51,54 @@ pub fn parse_input(fname: &str) -> AocResult<(Vec<Circle>, Vec<Point>)> {
   Ok((circles, beacons))
 }
 
-enum Event {
-  In(i32),
-  Out(i32),
+fn covered_row(input: &[Circle], y: i32) -> IntervalSet<i32> {
+  input
+    .iter()
+    .filter_map(|c| Some(c.first_at(y)?..c.last_at(y)? + 1))
+    .collect()
 }
 
-fn part1(input: &[Circle], beacons: &[Point]) -> AocResult<i32> {
-  let target = 2000000;
+fn part1(input: &[Circle], beacons: &[Point], target: i32) -> AocResult<i32> {
+  let covered = covered_row(input, target);
 
-  let mut events = vec![];
-  for c in input {
-    if let Some(first) = c.first_at(target) {
-      events.push(Event::In(first));
-    }
-    if let Some(last) = c.last_at(target) {
-      events.push(Event::Out(last + 1));
-    }
-  }
+  let res = covered.len()
+    - beacons
+      .iter()
+      .filter(|b| b.y == target && covered.contains(b.x))
+      .unique()
+      .count() as i32;
+  Ok(res)
+}
 
-  events.sort_by_key(|e| match e {
-    Event::In(x) => *x,
-    Event::Out(x) => *x,
-  });
-
-  let mut res = 0;
-  let mut last = 0;
-  let mut d = 0;
-  for e in events {
-    match e {
-      Event::In(x) => {
-        if d == 0 {
-          last = x;
-        }
-        d += 1;
-      }
-      Event::Out(x) => {
-        d -= 1;
-        if d == 0 {
-          res += x - last;
-        }
+/// Ranges of lines squeezed between two diamond edges among `offsets`, which
+/// may have other edges in between.
+fn close_lines(mut offsets: Vec<i32>) -> Vec<(i32, i32)> {
+  offsets.sort_unstable();
+  offsets.dedup();
+  let mut res = vec![];
+  for (i, &a) in offsets.iter().enumerate() {
+    for &b in offsets[i + 1..].iter().take_while(|&&b| b - a <= 4) {
+      if b - a >= 2 {
+        res.push((a + 1, b - 1));
       }
     }
   }
-
-  res -= beacons.iter().filter(|b| b.y == target).unique().count() as i32;
-  Ok(res)
+  res
 }
 
 // The point we are looking for must be at edges of diamonds.
 // Where 4 of them are close. 2 / lines and 2 \ lines
-fn part2(input: &[Circle], _: &[Point]) -> AocResult<i64> {
-  // Get x+y = const lines
-  let mut offs_up = vec![];
-  for c in input {
-    offs_up.push(c.x() + c.y() - c.rad as i32);
-    offs_up.push(c.x() + c.y() + c.rad as i32);
-  }
-  offs_up.sort_unstable();
-  let mut close_up = vec![];
-  for [a, b] in offs_up.array_windows() {
-    if b - a <= 4 {
-      close_up.push((a + 1, b - 1));
-    }
-  }
+fn part2(input: &[Circle], bound: i32) -> AocResult<i64> {
+  let area = 0..=bound;
 
+  let edges = |offset: fn(&Circle) -> i32| {
+    let rad = |c: &Circle| c.rad as i32;
+    close_lines(input.iter().flat_map(|c| [offset(c) - rad(c), offset(c) + rad(c)]).collect())
+  };
+  // Get x+y = const lines
+  let close_up = edges(|c| c.x() + c.y());
   // Get -x+y = const lines
-  let mut offs_down = vec![];
-  for c in input {
-    offs_down.push(-c.x() + c.y() - c.rad as i32);
-    offs_down.push(-c.x() + c.y() + c.rad as i32);
-  }
-  offs_down.sort_unstable();
-  let mut close_down = vec![];
-  for [a, b] in offs_down.array_windows() {
-    if b - a <= 4 {
-      close_down.push((a + 1, b - 1));
-    }
-  }
+  let close_down = edges(|c| -c.x() + c.y());
 
   for ((up_low, up_high), (down_low, down_high)) in
     iproduct!(close_up.into_iter(), close_down.into_iter())
@@ -148,10 +110,13 @@ fn part2(input: &[Circle], _: &[Point]) -> AocResult<i64> {
         let x = (up - down) / 2;
         let y = (up + down) / 2;
         let p = Point::new(x, y);
+        if !area.contains(&x) || !area.contains(&y) {
+          continue;
+        }
 
         let mut ok = true;
         for c in input {
-          if c.center.dist(p) <= c.rad {
+          if c.center.manhattan(p) as u32 <= c.rad {
             ok = false;
           }
         }
@@ -162,10 +127,17 @@ fn part2(input: &[Circle], _: &[Point]) -> AocResult<i64> {
     }
   }
 
-  Ok(-1)
+  aoc_error("No uncovered point found")
 }
 
-pub fn run(input: &(Vec<Circle>, Vec<Point>)) -> AocResult<(i32, i64)> {
+/// Solves with part 1 looking at row `row` and part 2 searching x and y in
+/// `0..=bound`. The example uses much smaller ones than the real puzzle.
+pub fn solve(input: &(Vec<Circle>, Vec<Point>), row: i32, bound: i32) -> AocResult<(i32, i64)> {
   let (input, beacons) = input;
-  Ok((part1(input, beacons)?, part2(input, beacons)?))
+  Ok((part1(input, beacons, row)?, part2(input, bound)?))
+}
+
+#[aoc::solution(day 15: 1000 runs, expected 4907780 13639962836448)]
+pub fn run(input: &(Vec<Circle>, Vec<Point>)) -> AocResult<(i32, i64)> {
+  solve(input, 2000000, 4000000)
 }
@@ -1,8 +1,9 @@
 use ahash::AHashMap;
-use bstr::ByteSlice;
 use itertools::Itertools;
 
 use error::AocResult;
+use crate::utils::bits::Bits;
+use crate::utils::parser::Pattern;
 
 #[derive(Debug)]
 pub struct Valve {
@@ -13,28 +14,12 @@ pub struct Valve {
 
 pub fn parse_input(fname: &str) -> AocResult<Vec<Valve>> {
   let b = std::fs::read(fname)?;
+  let pattern = Pattern::new("Valve {name} has flow rate={i32}; {_} to {_} {list<,>}")?;
   Ok(
-    b.to_str_lossy()
-      .lines()
-      .map(|s| {
-        let v = s.split(" ").collect_vec();
-        let src = v[1];
-        let rate = v[4]
-          .strip_prefix("rate=")
-          .unwrap()
-          .strip_suffix(";")
-          .unwrap();
-        let dsts = &v[9..];
-
-        Valve {
-          name: src.to_string(),
-          rate: rate.parse().unwrap(),
-          dsts: dsts
-            .iter()
-            .map(|x| x.trim_matches(',').to_string())
-            .collect_vec(),
-        }
-      })
+    pattern
+      .parse_lines(&b)?
+      .into_iter()
+      .map(|(name, rate, dsts)| Valve { name, rate, dsts })
       .collect_vec(),
   )
 }
@@ -76,12 +61,12 @@ type IxType = u8;
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 struct State {
   cur: IxType,
-  opened: u16,
+  opened: Bits<u16>,
 }
 
 impl State {
-  fn is_opened(&self, i: IxType) -> bool { self.opened & (1 << i) > 0 }
-  fn open(&mut self, i: IxType) { self.opened |= 1 << i; }
+  fn is_opened(&self, i: IxType) -> bool { self.opened.contains(i as usize) }
+  fn open(&mut self, i: IxType) { self.opened.insert(i as usize); }
 }
 
 fn compute_best(
@@ -96,38 +81,20 @@ fn compute_best(
   let max_t2 = 26;
   for (state, res) in released[max_t2].iter() {
     // Total flow at cut-off.
-    let mut i = 0;
-    let mut n = state.opened;
-    let mut total_flow = 0;
-    while n != 0 {
-      if n & 1 == 1 {
-        total_flow += g.flow[i];
-      }
-      i += 1;
-      n /= 2;
-    }
+    let total_flow: i32 = state.opened.iter().map(|i| g.flow[i]).sum();
 
     let compensated = *res - total_flow * (max_t - max_t2) as i32;
-    let b = &mut best[state.opened as usize];
+    let b = &mut best[state.opened.0 as usize];
     *b = (*b).max(compensated);
   }
   best
 }
 
 fn distinct_subsets(n: usize) -> impl Iterator<Item = (usize, usize)> {
-  // Iterates over distinct subsets of bits.
-  // Way more noise than the loop version :<
-  (0..n)
-    .map(move |i| {
-      let m = i ^ (n - 1);
-      let n_subsets = 1<<m.count_ones();
-      (0..n_subsets).scan(m, move |x, _| {
-          let res = *x;
-          *x = (*x - 1) & m;
-          Some((i, res))
-      })
-    })
-    .flatten()
+  // Pairs of disjoint subsets of n bits.
+  (0..1 << n).flat_map(move |i| {
+    Bits::<u16>(i as u16).complement(n).subsets().map(move |j| (i, j.0 as usize))
+  })
 }
 
 fn solve(input: &[Valve]) -> AocResult<(i32, i32)> {
@@ -137,7 +104,7 @@ fn solve(input: &[Valve]) -> AocResult<(i32, i32)> {
 
   // Simulate.
   let mut released = vec![AHashMap::new(); max_t + 1];
-  let start = State { cur: g.map["AA"], opened: 0 };
+  let start = State { cur: g.map["AA"], opened: Bits::empty() };
   released[0].insert(start, 0);
 
   for t in 1..released.len() {
@@ -174,7 +141,7 @@ fn solve(input: &[Valve]) -> AocResult<(i32, i32)> {
 
   let best = compute_best(input, released, g, max_t);
 
-  let res2 = distinct_subsets(best.len())
+  let res2 = distinct_subsets(best.len().trailing_zeros() as usize)
     .map(|(i, j)| best[i] + best[j])
     .max()
     .unwrap_or(-1);
@@ -182,4 +149,21 @@ fn solve(input: &[Valve]) -> AocResult<(i32, i32)> {
   Ok((res1, res2))
 }
 
+#[aoc::solution(day 16: 10 runs, expected 1737 2216, tags(slow))]
 pub fn run(input: &[Valve]) -> AocResult<(i32, i32)> { solve(input) }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn distinct_subsets_are_disjoint_and_complete() {
+    for n in 0..6 {
+      let pairs = distinct_subsets(n).collect_vec();
+      assert!(pairs.iter().all(|&(i, j)| i & j == 0 && (i | j) < 1 << n));
+      // Each element goes to the first set, the second or neither.
+      assert_eq!(pairs.len(), 3usize.pow(n as u32));
+      assert_eq!(pairs.iter().unique().count(), pairs.len());
+    }
+  }
+}
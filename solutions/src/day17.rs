@@ -4,6 +4,7 @@ use bstr::ByteSlice;
 use itertools::iproduct;
 
 use error::AocResult;
+use crate::utils::cycle;
 
 pub fn parse_input(fname: &str) -> AocResult<Vec<u8>> {
   let b = std::fs::read(fname)?;
@@ -41,6 +42,9 @@ macro_rules! rock {
   };
 }
 
+// Not a `Grid`: each 7 wide row is a bitmask, so a rock is tested and placed
+// with a few shifts, the cycle key (`surface`) is cheap to flood fill from the
+// rows, and the board grows upwards without bound.
 struct Board {
   data: Vec<u16>,
   top: isize,
@@ -56,6 +60,30 @@ impl Board {
     }
     self.data[y as usize] >> x & 1 > 0
   }
+
+  /// Free cells a falling rock could still reach, row by row down from the
+  /// top. Rocks only move sideways and down, so everything else acts as rock
+  /// and two boards with the same surface settle future rocks the same way.
+  fn surface(&self) -> Vec<u16> {
+    let mut res = vec![];
+    let mut reach = 0x7f;
+    for y in (0..self.top).rev() {
+      let free = !self.data[y as usize] & 0x7f;
+      reach &= free;
+      loop {
+        let next = (reach | reach << 1 | reach >> 1) & free;
+        if next == reach {
+          break;
+        }
+        reach = next;
+      }
+      if reach == 0 {
+        break;
+      }
+      res.push(reach);
+    }
+    res
+  }
 }
 
 #[allow(unused)]
@@ -83,6 +111,9 @@ fn draw(board: &Board, rock: &Rock) {
 fn simulate1(board: &mut Board, mut rock: Rock, jets: &mut Cycle<Iter<u8>>) -> i32 {
   rock.x = 2;
   rock.y = board.top + 3;
+  if board.data.len() < (rock.y + 4) as usize {
+    board.data.resize(2 * board.data.len(), 0);
+  }
 
   let mut n_jets = 0;
 
@@ -130,7 +161,8 @@ fn simulate1(board: &mut Board, mut rock: Rock, jets: &mut Cycle<Iter<u8>>) -> i
   n_jets
 }
 
-fn part1(input: &[u8]) -> AocResult<i32> {
+/// Height of the tower after dropping `n` rocks one by one.
+fn drop_rocks(input: &[u8], n: usize) -> isize {
   let rocks = [
     rock!(0b0000
           0b0000
@@ -159,14 +191,17 @@ fn part1(input: &[u8]) -> AocResult<i32> {
 
   let mut board = Board::new();
 
-  for rock in rocks.take(2022) {
+  for rock in rocks.take(n) {
     simulate1(&mut board, rock, &mut jets);
   }
 
-  Ok(board.top as i32)
+  board.top
 }
 
-fn part2(input: &[u8]) -> AocResult<i64> {
+fn part1(input: &[u8]) -> AocResult<i32> { Ok(drop_rocks(input, 2022) as i32) }
+
+/// Height of the tower after `n` rocks, skipping ahead once it repeats.
+fn extrapolated_height(input: &[u8], n: usize) -> i64 {
   let rocks = [
     rock!(0b0000
           0b0000
@@ -190,27 +225,58 @@ fn part2(input: &[u8]) -> AocResult<i64> {
           0b1100, 2, 2),
   ];
 
-  let rocks = rocks.into_iter().cycle();
-  let mut jets = input.into_iter().cycle();
+  let mut rocks = rocks.into_iter().enumerate().cycle();
+  let mut jets = input.iter().cycle();
+  let mut jet_ix = 0;
 
   let mut board = Board::new();
 
-  // TODO(algmyr): detect cycle programmatically
-  let cycle_len = 1715;
-  let top_delta = 2613;
-
-  let lim = 1000000000000;
-  let to_sim = lim%cycle_len + cycle_len;
-
-  for rock in rocks.take(to_sim) {
-    simulate1(&mut board, rock, &mut jets);
-  }
-
-  let res = board.top as usize + (lim - to_sim)/cycle_len*top_delta;
-
-  Ok(res as i64)
+  cycle::extrapolate(n, 0, || {
+    let (rock_ix, rock) = rocks.next().unwrap();
+    jet_ix = (jet_ix + simulate1(&mut board, rock, &mut jets) as usize) % input.len();
+    ((rock_ix, jet_ix, board.surface()), board.top as i64)
+  })
 }
 
+fn part2(input: &[u8]) -> AocResult<i64> { Ok(extrapolated_height(input, 1000000000000)) }
+
+#[aoc::solution(day 17: 1000 runs, expected 3071 1523615160362)]
 pub fn run(input: &[u8]) -> AocResult<(i32, i64)> {
   Ok((part1(input)?, part2(input)?))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE: &[u8] = b">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+  #[test]
+  fn extrapolation_matches_simulation() {
+    // A fixed pseudo random jet pattern too, where rocks can fall far below
+    // the top before settling.
+    let mut x = 1u32;
+    let jets = (0..2000)
+      .map(|_| {
+        x = x.wrapping_mul(1103515245).wrapping_add(12345);
+        if x >> 16 & 1 == 0 { b'<' } else { b'>' }
+      })
+      .collect::<Vec<_>>();
+    for input in [SAMPLE, &jets] {
+      for n in [0, 1, 2022, 5000, 12345] {
+        assert_eq!(extrapolated_height(input, n), drop_rocks(input, n) as i64, "{} rocks", n);
+      }
+    }
+  }
+
+  #[test]
+  fn surface() {
+    let mut board = Board::new();
+    assert!(board.surface().is_empty());
+    // Blocked on the left at the top, a single gap under that and a pocket
+    // below the gap that no rock can get into.
+    board.data[..3].copy_from_slice(&[0b1111110, 0b1011111, 0b0000001]);
+    board.top = 3;
+    assert_eq!(board.surface(), [0b1111110, 0b0100000]);
+  }
+}
@@ -1,110 +1,56 @@
-use std::ops::{Index, IndexMut};
-
 use itertools::Itertools;
 
 use error::AocResult;
+use crate::utils::bits::BitGrid3;
+use crate::utils::point::Point3;
 use crate::utils::read_all_nums_from_bytes;
+use crate::utils::search::{self, Dense};
+
+type Point = Point3<i8>;
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<(i8, i8, i8)>> {
+pub fn parse_input(fname: &str) -> AocResult<Vec<Point>> {
   let b = std::fs::read(fname)?;
   let res = read_all_nums_from_bytes(&b)?
     .into_iter()
     .tuples()
+    .map(|(x, y, z)| Point::new(x, y, z))
     .collect_vec();
   Ok(res)
 }
 
 const N: usize = 30;
-struct Space {
-  seen: [[[bool; N]; N]; N],
-}
-impl Space {
-  fn new() -> Space { Space { seen: [[[false; N]; N]; N] } }
-}
-impl Index<[i8; 3]> for Space {
-  type Output = bool;
-
-  fn index(&self, index: [i8; 3]) -> &Self::Output {
-    let [x, y, z] = index;
-    unsafe {
-      self
-        .seen
-        .get_unchecked(x as usize)
-        .get_unchecked(y as usize)
-        .get_unchecked(z as usize)
-    }
-  }
-}
-impl IndexMut<[i8; 3]> for Space {
-  fn index_mut(&mut self, index: [i8; 3]) -> &mut Self::Output {
-    let [x, y, z] = index;
-    unsafe {
-      self
-        .seen
-        .get_unchecked_mut(x as usize)
-        .get_unchecked_mut(y as usize)
-        .get_unchecked_mut(z as usize)
-    }
-  }
-}
 
-fn solve(input: &[(i8, i8, i8)]) -> AocResult<(usize, usize)> {
-  let mut seen = Space::new();
+fn solve(input: &[Point]) -> AocResult<(usize, usize)> {
+  let mut lava = BitGrid3::new(N, N, N);
+  let is_lava = |lava: &BitGrid3, p: Point| lava.get(p.x as usize, p.y as usize, p.z as usize);
 
   let mut res = 0;
-  for &(x, y, z) in input {
-    let x = x + 1;
-    let y = y + 1;
-    let z = z + 1;
+  for &p in input {
+    let p = p + Point::new(1, 1, 1);
+    lava.set(p.x as usize, p.y as usize, p.z as usize, true);
 
     res += 6;
-    for (dx, dy, dz) in [
-      (1, 0, 0),
-      (0, 1, 0),
-      (0, 0, 1),
-      (-1, 0, 0),
-      (0, -1, 0),
-      (0, 0, -1),
-    ]{
-      let p = [x+dx, y+dy, z+dz];
-      seen[[x, y, z]] = true;
-      if seen[p] {
+    for q in p.neighbors6() {
+      if is_lava(&lava, q) {
         res -= 2;
       }
     }
   }
 
-  let mut res2 = 0;
-  let mut stack = vec![[0, 0 ,0]];
-  let mut visited = Space::new();
   let r = 0..(N as i8);
-  while let Some(p) = stack.pop() {
-    let [x, y, z] = p;
-    if visited[p] {
-      continue;
-    }
-    visited[p] = true;
-
-    for [dx, dy, dz] in [
-      [1, 0, 0],
-      [0, 1, 0],
-      [0, 0, 1],
-      [-1, 0, 0],
-      [0, -1, 0],
-      [0, 0, -1],
-    ]{
-      let q = [x+dx, y+dy, z+dz];
-      if r.contains(&q[0]) && r.contains(&q[1]) && r.contains(&q[2]) {
-        if !seen[q] {
-          stack.push(q);
-        } else {
-          res2 += 1;
-        }
-      }
-    }
-  }
+  let in_range = |q: &Point| r.contains(&q.x) && r.contains(&q.y) && r.contains(&q.z);
+  let (lava, in_range) = (&lava, &in_range);
+  let index = |p: &Point| (p.x as usize * N + p.y as usize) * N + p.z as usize;
+  // Counts the lava faces seen from each air cube while filling.
+  let mut res2 = 0;
+  search::flood_fill_in(Dense::<bool, _>::new(N * N * N, index), Point::default(), |p| {
+    let sides = p.neighbors6().into_iter().filter(move |q| in_range(q));
+    res2 += sides.clone().filter(|&q| is_lava(lava, q)).count();
+    sides.filter(move |&q| !is_lava(lava, q))
+  });
 
   Ok((res, res2))
 }
 
-pub fn run(input: &[(i8, i8, i8)]) -> AocResult<(usize, usize)> { solve(input) }
+#[aoc::solution(day 18: 1000 runs, expected 4450 2564)]
+pub fn run(input: &[Point]) -> AocResult<(usize, usize)> { solve(input) }
@@ -2,6 +2,7 @@ use itertools::Itertools;
 
 use error::AocResult;
 use crate::utils::read_all_nums_from_bytes;
+use crate::utils::search;
 
 #[derive(Debug)]
 pub struct Blueprint {
@@ -84,7 +85,7 @@ pub fn parse_input(fname: &str) -> AocResult<Vec<Blueprint>> {
   let b = std::fs::read(fname)?;
 
   let res = read_all_nums_from_bytes(&b)?
-    .array_chunks()
+    .as_chunks().0.iter()
     .map(
       |&[id, ore_ore, clay_ore, obs_ore, obs_clay, geode_ore, geode_clay]| Blueprint {
         id,
@@ -173,69 +174,72 @@ fn upper_bound_sim(
   resources.geode
 }
 
-fn do_blueprint(blueprint: &Blueprint, lim: i32) -> i32 {
-  let mut stack = vec![(0, Resources::new(0, 0, 0, 0), Bots::new(1, 0, 0, 0))];
-  let mut max_geodes = 0;
-  while let Some((turn, resources, bots)) = stack.pop() {
-    if turn > lim {
-      continue;
-    }
-    if turn == lim {
-      if resources.geode > max_geodes {
-        max_geodes = resources.geode;
-      }
-      continue;
-    }
+type State = (i32, Resources, Bots);
 
-    // Ditch useless states.
-    if upper_bound_sim(blueprint, lim, turn, &resources, &bots) <= max_geodes {
-      continue;
+fn children(
+  blueprint: &Blueprint,
+  lim: i32,
+  &(turn, resources, bots): &State,
+  res: &mut Vec<State>,
+) {
+  // Purchases that finish too late are useless.
+  let mut push = |state: State| {
+    if state.0 <= lim {
+      res.push(state);
     }
+  };
 
-    // Buy geode.
-    if let Some((delta, new_res)) = blueprint.when_buy_geode(&resources, &bots) {
-      stack.push((
+  // Buy geode.
+  if let Some((delta, new_res)) = blueprint.when_buy_geode(&resources, &bots) {
+    push((
+      turn + delta + 1,
+      update_resources(&new_res, &bots, 1),
+      Bots { geode: bots.geode + 1, ..bots },
+    ));
+  }
+  // Buy obsidian.
+  if bots.obsidian < blueprint.max_obsidian() {
+    if let Some((delta, new_res)) = blueprint.when_buy_obsidian(&resources, &bots) {
+      push((
         turn + delta + 1,
         update_resources(&new_res, &bots, 1),
-        Bots { geode: bots.geode + 1, ..bots },
+        Bots { obsidian: bots.obsidian + 1, ..bots },
       ));
     }
-    // Buy obsidian.
-    if bots.obsidian < blueprint.max_obsidian() {
-      if let Some((delta, new_res)) = blueprint.when_buy_obsidian(&resources, &bots) {
-        stack.push((
-          turn + delta + 1,
-          update_resources(&new_res, &bots, 1),
-          Bots { obsidian: bots.obsidian + 1, ..bots },
-        ));
-      }
-    }
-    // Buy clay.
-    if bots.clay < blueprint.max_clay() {
-      if let Some((delta, new_res)) = blueprint.when_buy_clay(&resources, &bots) {
-        stack.push((
-          turn + delta + 1,
-          update_resources(&new_res, &bots, 1),
-          Bots { clay: bots.clay + 1, ..bots },
-        ));
-      }
+  }
+  // Buy clay.
+  if bots.clay < blueprint.max_clay() {
+    if let Some((delta, new_res)) = blueprint.when_buy_clay(&resources, &bots) {
+      push((
+        turn + delta + 1,
+        update_resources(&new_res, &bots, 1),
+        Bots { clay: bots.clay + 1, ..bots },
+      ));
     }
-    // Buy ore.
-    if bots.ore < blueprint.max_ore() {
-      if let Some((delta, new_res)) = blueprint.when_buy_ore(&resources, &bots) {
-        stack.push((
-          turn + delta + 1,
-          update_resources(&new_res, &bots, 1),
-          Bots { ore: bots.ore + 1, ..bots },
-        ));
-      }
+  }
+  // Buy ore.
+  if bots.ore < blueprint.max_ore() {
+    if let Some((delta, new_res)) = blueprint.when_buy_ore(&resources, &bots) {
+      push((
+        turn + delta + 1,
+        update_resources(&new_res, &bots, 1),
+        Bots { ore: bots.ore + 1, ..bots },
+      ));
     }
-
-    // Do nothing.
-    let resources = update_resources(&resources, &bots, lim - turn);
-    stack.push((lim, resources, bots));
   }
-  max_geodes
+
+  // Do nothing.
+  push((lim, update_resources(&resources, &bots, lim - turn), bots));
+}
+
+fn do_blueprint(blueprint: &Blueprint, lim: i32) -> i32 {
+  search::branch_and_bound(
+    (0, Resources::new(0, 0, 0, 0), Bots::new(1, 0, 0, 0)),
+    0,
+    |state, stack| children(blueprint, lim, state, stack),
+    |&(turn, resources, _)| (turn == lim).then_some(resources.geode),
+    |(turn, resources, bots)| upper_bound_sim(blueprint, lim, *turn, resources, bots),
+  )
 }
 
 fn part1(input: &[Blueprint]) -> AocResult<i32> {
@@ -254,6 +258,7 @@ fn part2(input: &[Blueprint]) -> AocResult<i32> {
   Ok(res)
 }
 
+#[aoc::solution(day 19: 100 runs, expected 1565 10672, tags(slow))]
 pub fn run(input: &[Blueprint]) -> AocResult<(i32, i32)> {
   Ok((part1(input)?, part2(input)?))
 }
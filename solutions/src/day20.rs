@@ -133,4 +133,5 @@ fn part2(input: &[i64]) -> AocResult<i64> {
   shuffle(&input, 10)
 }
 
+#[aoc::solution(day 20: 100 runs, expected 11616 9937909178485)]
 pub fn run(input: &[i64]) -> AocResult<(i64, i64)> { Ok((part1(input)?, part2(input)?)) }
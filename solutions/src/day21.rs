@@ -20,25 +20,23 @@ impl Command {
   fn new_op(arg1: &[u8], op: &[u8], arg2: &[u8]) -> Command {
     Command::Operation(to_int(arg1), op[0], to_int(arg2))
   }
-  fn new_const(arg: &[u8]) -> Command {
-    Command::Constant(crate::utils::num_from_bytes(arg))
+  fn new_const(arg: &[u8]) -> AocResult<Command> {
+    Ok(Command::Constant(crate::utils::num_from_bytes(arg)?))
   }
 }
 
 pub fn parse_input(fname: &str) -> AocResult<Vec<(u32, Command)>> {
   let b = std::fs::read(fname)?;
-  Ok(
-    b.trim()
-      .split(|c| *c == b'\n')
-      .map(
-        |bs| match bs.split(|&c| c == b' ').collect_vec().as_slice() {
-          &[name, arg1, op, arg2] => (to_int(name), Command::new_op(arg1, op, arg2)),
-          &[name, arg] => (to_int(name), Command::new_const(arg)),
-          _ => panic!(":<"),
-        },
-      )
-      .collect_vec(),
-  )
+  b.trim()
+    .split(|c| *c == b'\n')
+    .map(
+      |bs| match bs.split(|&c| c == b' ').collect_vec().as_slice() {
+        &[name, arg1, op, arg2] => Ok((to_int(name), Command::new_op(arg1, op, arg2))),
+        &[name, arg] => Ok((to_int(name), Command::new_const(arg)?)),
+        _ => panic!(":<"),
+      },
+    )
+    .collect()
 }
 
 fn part1(input: &[(u32, Command)]) -> AocResult<f64> {
@@ -166,6 +164,7 @@ fn part2(input: &[(u32, Command)]) -> AocResult<i64> {
   Ok(res)
 }
 
+#[aoc::solution(day 21: 1000 runs, expected 256997859093114 3952288690726)]
 pub fn run(input: &[(u32, Command)]) -> AocResult<(f64, i64)> {
   Ok((part1(input)?, part2(input)?))
 }
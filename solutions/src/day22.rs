@@ -2,32 +2,36 @@ use std::hint::unreachable_unchecked;
 use std::ops::Range;
 
 use bstr::ByteSlice;
-use derive_more::{Add, AddAssign, Constructor, Sub, SubAssign};
 use itertools::Itertools;
 
-use error::AocResult;
+use error::{aoc_error, AocResult};
+use crate::utils::grid::Grid;
+use crate::utils::point::{Dir, Point2};
 
-pub fn parse_input(fname: &str) -> AocResult<Vec<Vec<u8>>> {
+type InputType = (Grid<u8>, Vec<Action>);
+
+pub fn parse_input(fname: &str) -> AocResult<InputType> {
   let b = std::fs::read(fname)?;
-  Ok(
-    b.trim_end()
-      .split(|c| *c == b'\n')
-      .map(|bs| bs.to_vec())
-      .collect_vec(),
-  )
+  let Some((board, instructions)) = b.trim_end().split_once_str(b"\n\n") else {
+    return aoc_error("Missing instructions");
+  };
+  Ok((
+    Grid::from_bytes_padded(board, b' '),
+    parse_instructions(instructions),
+  ))
 }
 
 #[derive(Clone, Copy)]
-enum Action {
+pub enum Action {
   Forward(usize),
   Left,
   Right,
 }
 
-fn parse_instructions(instruction_bytes: Vec<u8>) -> Vec<Action> {
+fn parse_instructions(instruction_bytes: &[u8]) -> Vec<Action> {
   let mut instructions = vec![];
   let mut steps = 0;
-  for b in instruction_bytes {
+  for &b in instruction_bytes {
     if b.is_ascii_digit() {
       steps = 10 * steps + (b - b'0') as usize;
       continue;
@@ -50,34 +54,10 @@ fn parse_instructions(instruction_bytes: Vec<u8>) -> Vec<Action> {
   instructions
 }
 
-#[derive(Clone, Copy)]
-struct Heading {
-  heading: i32,
-}
-impl Heading {
-  fn rot(&self, n: i32) -> Heading {
-    Heading { heading: (self.heading + n).rem_euclid(4) }
-  }
-  fn dir(&self) -> Point {
-    match self.heading {
-      0 => Point::new(1, 0),
-      1 => Point::new(0, 1),
-      2 => Point::new(-1, 0),
-      3 => Point::new(0, -1),
-      _ => panic!("where are you heading? :<"),
-    }
-  }
-  fn value(&self) -> i32 { self.heading }
-}
-
-#[derive(Debug, Clone, Copy, Add, Sub, AddAssign, SubAssign, Constructor)]
-struct Point {
-  x: i32,
-  y: i32,
-}
+type Point = Point2<i32>;
 
 #[allow(unused)]
-fn draw_board(data: &[Vec<u8>], pos: Point, rad: i32, heading: Heading) {
+fn draw_board(data: &Grid<u8>, pos: Point, rad: i32, heading: Dir) {
   let mut out = vec![];
   let min_x = pos.x - rad;
   let max_x = pos.x + rad;
@@ -86,10 +66,7 @@ fn draw_board(data: &[Vec<u8>], pos: Point, rad: i32, heading: Heading) {
   for y in min_y..=max_y {
     let mut s = String::new();
     for x in min_x..=max_x {
-      let c = *data
-        .get(y as usize)
-        .and_then(|row| row.get(x as usize))
-        .unwrap_or(&b' ');
+      let c = *data.get(x as usize, y as usize).unwrap_or(&b' ');
       if x == pos.x && y == pos.y {
         assert!(c == b'.');
         s.push(b">v<^"[heading.value() as usize].into());
@@ -103,52 +80,40 @@ fn draw_board(data: &[Vec<u8>], pos: Point, rad: i32, heading: Heading) {
 }
 
 trait Board {
-  fn step(&self, pos: Point, heading: Heading) -> Option<(Point, Heading)>;
+  fn step(&self, pos: Point, heading: Dir) -> Option<(Point, Dir)>;
 }
 
 struct WrapBoard {
-  data: Vec<Vec<u8>>,
+  data: Grid<u8>,
   xlimits: Vec<Range<i32>>,
   ylimits: Vec<Range<i32>>,
 }
 impl WrapBoard {
-  fn new(input: &[Vec<u8>]) -> WrapBoard {
-    let mut xlimits = vec![];
-    let mut ylimits = vec![];
-    for row in input {
-      let first = row.iter().position(|&b| b != b' ').unwrap();
-      let last = row.len(); // Assume no trailing whitespace
-      xlimits.push(first as i32..last as i32);
+  fn new(input: &Grid<u8>) -> WrapBoard {
+    // Range of non-blank cells along a row or column.
+    fn limits<'a>(line: impl DoubleEndedIterator<Item = &'a u8> + ExactSizeIterator) -> Range<i32> {
+      let mut line = line.enumerate().filter(|(_, &b)| b != b' ');
+      let first = line.next().unwrap().0;
+      let last = line.next_back().map_or(first, |(i, _)| i);
+      first as i32..last as i32 + 1
     }
-    let max_x = xlimits.iter().map(|r| r.end).max().unwrap() as usize;
-    for x in 0..max_x {
-      let mut first = None;
-      let mut last = None;
-      for y in 0..input.len() {
-        if let Some(&b) = input[y].get(x) {
-          if b != b' ' {
-            first = first.or(Some(y));
-            last = Some(y);
-          }
-        }
-      }
-      ylimits.push(first.unwrap() as i32..last.unwrap() as i32 + 1);
-    }
-    WrapBoard { data: input.to_vec(), xlimits, ylimits }
+    let xlimits = input.rows().map(|row| limits(row.iter())).collect_vec();
+    let ylimits = input.columns().map(limits).collect_vec();
+    WrapBoard { data: input.clone(), xlimits, ylimits }
   }
 }
 impl Board for WrapBoard {
-  fn step(&self, pos: Point, heading: Heading) -> Option<(Point, Heading)> {
+  fn step(&self, pos: Point, heading: Dir) -> Option<(Point, Dir)> {
     fn wrap(x: i32, range: &Range<i32>) -> i32 {
       (x - range.start).rem_euclid(range.len() as i32) + range.start
     }
 
-    let new = pos + heading.dir();
+    let new = pos + heading.delta();
     let new = Point::new(
       wrap(new.x, &self.xlimits[pos.y as usize]),
       wrap(new.y, &self.ylimits[pos.x as usize]),
     );
-    if self.data[new.y as usize][new.x as usize] == b'.' {
+    if self.data[(new.x as usize, new.y as usize)] == b'.' {
       Some((new, heading))
     } else {
       None
@@ -159,36 +124,13 @@ impl Board for WrapBoard {
 const CUBE_SIZE: i32 = 50;
 
 struct CubeBoard {
-  data: Vec<Vec<u8>>,
+  data: Grid<u8>,
 }
 impl CubeBoard {
-  fn new(input: &[Vec<u8>]) -> Self {
-    let mut xlimits = vec![];
-    let mut ylimits = vec![];
-    for row in input {
-      let first = row.iter().position(|&b| b != b' ').unwrap();
-      let last = row.len(); // Assume no trailing whitespace
-      xlimits.push(first as i32..last as i32);
-    }
-    let max_x = xlimits.iter().map(|r| r.end).max().unwrap() as usize;
-    for x in 0..max_x {
-      let mut first = None;
-      let mut last = None;
-      for y in 0..input.len() {
-        if let Some(&b) = input[y].get(x) {
-          if b != b' ' {
-            first = first.or(Some(y));
-            last = Some(y);
-          }
-        }
-      }
-      ylimits.push(first.unwrap() as i32..last.unwrap() as i32 + 1);
-    }
-    Self { data: input.to_vec() }
-  }
+  fn new(input: &Grid<u8>) -> Self { Self { data: input.clone() } }
 }
 impl Board for CubeBoard {
-  fn step(&self, pos: Point, heading: Heading) -> Option<(Point, Heading)> {
+  fn step(&self, pos: Point, heading: Dir) -> Option<(Point, Dir)> {
     fn face(p: Point) -> i32 {
       let fx = p.x / CUBE_SIZE;
       let fy = p.y / CUBE_SIZE;
@@ -235,11 +177,11 @@ impl Board for CubeBoard {
 
     fn transform(
       pos: Point,
-      heading: Heading,
+      heading: Dir,
       to: i32,
       target: i32,
       rot: i32,
-    ) -> (Point, Heading) {
+    ) -> (Point, Dir) {
       fn rotate(pos: Point, rot: i32) -> Point {
         assert!(0 <= pos.x && pos.x < CUBE_SIZE);
         assert!(0 <= pos.y && pos.y < CUBE_SIZE);
@@ -256,7 +198,7 @@ impl Board for CubeBoard {
       (new_point, new_heading)
     }
 
-    let new = pos + heading.dir();
+    let new = pos + heading.delta();
     let new = Point::new(
       new.x.rem_euclid(3 * CUBE_SIZE),
       new.y.rem_euclid(4 * CUBE_SIZE),
@@ -297,7 +239,7 @@ impl Board for CubeBoard {
       0x7 -> 0x9 (via 0xA) rot 1
       0x9 -> 0x7 (via 0xA) rot -1
     );
-    if self.data[new.y as usize][new.x as usize] == b'.' {
+    if self.data[(new.x as usize, new.y as usize)] == b'.' {
       Some((new, new_heading))
     } else {
       None
@@ -307,7 +249,7 @@ impl Board for CubeBoard {
 
 fn solve(instructions: &[Action], board: &impl Board) -> AocResult<i32> {
   let mut pos = Point::new(CUBE_SIZE, 0);
-  let mut heading = Heading { heading: 0 };
+  let mut heading = Dir::Right;
 
   for &inst in instructions {
     match inst {
@@ -322,10 +264,10 @@ fn solve(instructions: &[Action], board: &impl Board) -> AocResult<i32> {
         }
       }
       Action::Left => {
-        heading = heading.rot(-1);
+        heading = heading.left();
       }
       Action::Right => {
-        heading = heading.rot(1);
+        heading = heading.right();
       }
     }
   }
@@ -336,12 +278,11 @@ fn solve(instructions: &[Action], board: &impl Board) -> AocResult<i32> {
   Ok(res)
 }
 
-pub fn run(input: &[Vec<u8>]) -> AocResult<(i32, i32)> {
-  let mut input = input.to_vec();
-  let instructions = parse_instructions(input.pop().unwrap());
-  input.pop();
+#[aoc::solution(day 22: 1000 runs, expected 30552 184106)]
+pub fn run(input: &InputType) -> AocResult<(i32, i32)> {
+  let (board, instructions) = input;
   Ok((
-    solve(&instructions, &WrapBoard::new(&input))?,
-    solve(&instructions, &CubeBoard::new(&input))?,
+    solve(instructions, &WrapBoard::new(board))?,
+    solve(instructions, &CubeBoard::new(board))?,
   ))
 }
@@ -1,44 +1,24 @@
-use std::ops::{Index, IndexMut};
-
 use bstr::ByteSlice;
 use itertools::Itertools;
 
 use error::AocResult;
+use crate::utils::grid::Grid;
+use crate::utils::point::{Dir, Point2};
 
-#[derive(Clone, Copy, Debug)]
-enum Dir {
-  NONE,
-  N,
-  S,
-  W,
-  E,
-}
-
-impl Dir {
-  fn value(&self) -> Option<(i32, i32)> {
-    match self {
-      Dir::N => Some((0, -1)),
-      Dir::S => Some((0, 1)),
-      Dir::W => Some((-1, 0)),
-      Dir::E => Some((1, 0)),
-      Dir::NONE => None,
-    }
-  }
-}
+type Point = Point2<i32>;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Elf {
-  x: i32,
-  y: i32,
-  step: Dir,
+  pos: Point,
+  step: Option<Dir>,
 }
 
 impl Elf {
-  fn moved(&self) -> Option<(i32, i32)> {
-    self.step.value().map(|(dx, dy)| (self.x + dx, self.y + dy))
-  }
+  fn moved(&self) -> Option<Point> { self.step.map(|dir| self.pos + dir.delta()) }
 }
 
+fn ix(p: Point) -> (usize, usize) { (p.x as usize, p.y as usize) }
+
 pub fn parse_input(fname: &str) -> AocResult<(Vec<Elf>, usize, usize)> {
   let b = std::fs::read(fname)?;
 
@@ -50,7 +30,7 @@ pub fn parse_input(fname: &str) -> AocResult<(Vec<Elf>, usize, usize)> {
     height += 1;
     for (x, c) in row.iter().enumerate() {
       if *c == b'#' {
-        res.push(Elf { x: x as i32, y: y as i32, step: Dir::NONE })
+        res.push(Elf { pos: Point::new(x as i32, y as i32), step: None })
       }
     }
   }
@@ -58,91 +38,52 @@ pub fn parse_input(fname: &str) -> AocResult<(Vec<Elf>, usize, usize)> {
   Ok((res, width, height))
 }
 
-type IndexType = i32;
-struct Space<T, const N: usize> {
-  data: [[T; N]; N],
-}
-impl<T: Default + Copy, const N: usize> Space<T, N> {
-  fn new() -> Self { Self { data: [[T::default(); N]; N] } }
-}
-impl<T, const N: usize> Index<[IndexType; 2]> for Space<T, N> {
-  type Output = T;
-
-  fn index(&self, index: [IndexType; 2]) -> &Self::Output {
-    let [x, y] = index;
-    unsafe {
-      self
-        .data
-        .get_unchecked(x as usize)
-        .get_unchecked(y as usize)
-    }
-  }
-}
-impl<T, const N: usize> IndexMut<[IndexType; 2]> for Space<T, N> {
-  fn index_mut(&mut self, index: [IndexType; 2]) -> &mut Self::Output {
-    let [x, y] = index;
-    unsafe {
-      self
-        .data
-        .get_unchecked_mut(x as usize)
-        .get_unchecked_mut(y as usize)
-    }
-  }
-}
-
 const SIZE: usize = 500;
 
 struct Simulation {
-  occupied: Space<bool, SIZE>,
-  count: Space<u8, SIZE>,
+  occupied: Grid<bool>,
+  count: Grid<u8>,
   dirs: [(u8, Dir); 4],
 }
 
 impl Simulation {
   fn new() -> Self {
     let dirs = [
-      (0b11100000_u8, Dir::N),
-      (0b00001110_u8, Dir::S),
-      (0b10000011_u8, Dir::W),
-      (0b00111000_u8, Dir::E),
+      (0b11100000_u8, Dir::Up),
+      (0b00001110_u8, Dir::Down),
+      (0b10000011_u8, Dir::Left),
+      (0b00111000_u8, Dir::Right),
     ];
-    Self { occupied: Space::<bool, SIZE>::new(), count: Space::<u8, SIZE>::new(), dirs }
+    Self { occupied: Grid::new(SIZE, SIZE, false), count: Grid::new(SIZE, SIZE, 0), dirs }
   }
 
   fn mark_occupied(&mut self, elves: &[Elf]) {
     for elf in elves {
-      self.occupied[[elf.x, elf.y]] = true;
+      self.occupied[ix(elf.pos)] = true;
     }
   }
 
   fn propose_moves(&mut self, elves: &mut [Elf]) {
-    fn neighbors(occupied: &Space<bool, SIZE>, x: i32, y: i32) -> u8 {
+    fn neighbors(occupied: &Grid<bool>, p: Point) -> u8 {
       // 012
       // 7 3
       // 654
-      let get = |x, y| occupied[[x, y]];
-      (get(x - 1, y - 1) as u8) << 7
-        | (get(x + 0, y - 1) as u8) << 6
-        | (get(x + 1, y - 1) as u8) << 5
-        | (get(x + 1, y + 0) as u8) << 4
-        | (get(x + 1, y + 1) as u8) << 3
-        | (get(x + 0, y + 1) as u8) << 2
-        | (get(x - 1, y + 1) as u8) << 1
-        | (get(x - 1, y + 0) as u8) << 0
+      // Elves never get near the edge.
+      p.neighbors8().into_iter().fold(0, |acc, q| {
+        let (x, y) = ix(q);
+        acc << 1 | unsafe { *occupied.get_unchecked(x, y) } as u8
+      })
     }
 
     for elf in elves {
-      let x = elf.x;
-      let y = elf.y;
-
-      let neigh = neighbors(&self.occupied, x, y);
+      let neigh = neighbors(&self.occupied, elf.pos);
 
       if neigh != 0 {
         for &(mask, dir) in &self.dirs {
           if neigh & mask == 0 {
-            elf.step = dir;
-            let (nx, ny) = elf.moved().unwrap();
-            self.count[[nx, ny]] += 1;
+            elf.step = Some(dir);
+            let new = elf.moved().unwrap();
+            self.count[ix(new)] += 1;
             break;
           }
         }
@@ -154,15 +95,14 @@ impl Simulation {
     let mut n_moves = 0;
     // Move.
     for elf in elves {
-      if let Some((nx, ny)) = elf.moved() {
-        self.occupied[[elf.x, elf.y]] = false;
-        if self.count[[nx, ny]] == 1 {
-          elf.x = nx;
-          elf.y = ny;
+      if let Some(new) = elf.moved() {
+        self.occupied[ix(elf.pos)] = false;
+        if self.count[ix(new)] == 1 {
+          elf.pos = new;
           n_moves += 1;
         }
-        self.count[[nx, ny]] = 0;
-        elf.step = Dir::NONE;
+        self.count[ix(new)] = 0;
+        elf.step = None;
       }
     }
     n_moves
@@ -170,9 +110,9 @@ impl Simulation {
 
   fn clear_unmoved(&mut self, elves: &mut [Elf]) {
     for elf in elves {
-      self.occupied[[elf.x, elf.y]] = false;
-      if let Some((nx, ny)) = elf.moved() {
-        self.count[[nx, ny]] = 0;
+      self.occupied[ix(elf.pos)] = false;
+      if let Some(new) = elf.moved() {
+        self.count[ix(new)] = 0;
       }
     }
   }
@@ -193,8 +133,7 @@ fn part1(input: &[Elf], width: usize, height: usize) -> AocResult<i32> {
   let mut elves = input
     .iter()
     .map(|elf| Elf {
-      x: elf.x + (SIZE / 2 - width / 2) as i32,
-      y: elf.y + (SIZE / 2 - height / 2) as i32,
+      pos: elf.pos + Point::new((SIZE / 2 - width / 2) as i32, (SIZE / 2 - height / 2) as i32),
       step: elf.step,
     })
     .collect_vec();
@@ -208,10 +147,10 @@ fn part1(input: &[Elf], width: usize, height: usize) -> AocResult<i32> {
   let mut max_x = 0;
   let mut max_y = 0;
   for elf in &elves {
-    min_x = min_x.min(elf.x);
-    min_y = min_y.min(elf.y);
-    max_x = max_x.max(elf.x);
-    max_y = max_y.max(elf.y);
+    min_x = min_x.min(elf.pos.x);
+    min_y = min_y.min(elf.pos.y);
+    max_x = max_x.max(elf.pos.x);
+    max_y = max_y.max(elf.pos.y);
   }
   let res = (max_x - min_x + 1) * (max_y - min_y + 1) - elves.len() as i32;
 
@@ -224,8 +163,7 @@ fn part2(input: &[Elf], width: usize, height: usize) -> AocResult<i32> {
   let mut elves = input
     .iter()
     .map(|elf| Elf {
-      x: elf.x + (SIZE / 2 - width / 2) as i32,
-      y: elf.y + (SIZE / 2 - height / 2) as i32,
+      pos: elf.pos + Point::new((SIZE / 2 - width / 2) as i32, (SIZE / 2 - height / 2) as i32),
       step: elf.step,
     })
     .collect_vec();
@@ -237,6 +175,7 @@ fn part2(input: &[Elf], width: usize, height: usize) -> AocResult<i32> {
   Ok(sim_it)
 }
 
+#[aoc::solution(day 23: 30 runs, expected 3987 938, tags(slow))]
 pub fn run(input: &(Vec<Elf>, usize, usize)) -> AocResult<(i32, i32)> {
   let (elves, width, height) = input;
   Ok((
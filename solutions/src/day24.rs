@@ -4,6 +4,8 @@ use bstr::ByteSlice;
 use itertools::Itertools;
 
 use error::AocResult;
+use crate::utils::grid::Grid;
+use crate::utils::point::Point2;
 
 #[derive(Copy, Clone)]
 pub struct Blizzard {
@@ -17,7 +19,7 @@ pub struct Board {
   height: i32,
   cols: Vec<Vec<Blizzard>>,
   rows: Vec<Vec<Blizzard>>,
-  cache: Vec<bool>,
+  cache: Grid<bool>,
 }
 
 impl Board {
@@ -27,7 +29,7 @@ impl Board {
     cols: Vec<Vec<Blizzard>>,
     rows: Vec<Vec<Blizzard>>,
   ) -> Self {
-    Self { width, height, cols, rows, cache: vec![false; (width*height) as usize] }
+    Self { width, height, cols, rows, cache: Grid::new(width as usize, height as usize, false) }
   }
 
   fn update_occupied(&mut self, t: i32) {
@@ -39,22 +41,19 @@ impl Board {
     for (x, bs) in self.cols.iter().enumerate() {
       for b in bs {
         let y = f(b, t, self.height - 2);
-        let ix = y*self.width + x as i32;
-        self.cache[ix as usize] = true;
+        self.cache[(x, y as usize)] = true;
       }
     }
     for (y, bs) in self.rows.iter().enumerate() {
       for b in bs {
         let x = f(b, t, self.width - 2);
-        let ix = y as i32*self.width + x;
-        self.cache[ix as usize] = true;
+        self.cache[(x as usize, y)] = true;
       }
     }
   }
 
   fn occupied(&self, x: i32, y: i32) -> bool {
-    let ix = y as i32*self.width + x;
-    self.cache[ix as usize]
+    self.cache[(x as usize, y as usize)]
   }
 }
 
@@ -82,11 +81,12 @@ pub fn parse_input(fname: &str) -> AocResult<Board> {
   Ok(Board::new(width as i32, height as i32, cols, rows))
 }
 
-fn shortest(start_t: i32, start: (i32, i32), target: (i32, i32), board: &Board) -> AocResult<i32> {
+type Point = Point2<i32>;
+
+fn shortest(start_t: i32, start: Point, target: Point, board: &Board) -> AocResult<i32> {
   let mut board = board.clone();
 
-  let ok =
-    move |(x, y)| (1..board.width - 1).contains(&x) && (1..board.height - 1).contains(&y);
+  let ok = move |p: Point| (1..board.width - 1).contains(&p.x) && (1..board.height - 1).contains(&p.y);
 
   let mut frontier = vec![start];
 
@@ -94,17 +94,17 @@ fn shortest(start_t: i32, start: (i32, i32), target: (i32, i32), board: &Board)
     board.update_occupied(t+1);
     let mut new_frontier = vec![start];
 
-    for (x, y) in frontier {
-      for neigh in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+    for p in frontier {
+      for neigh in p.neighbors4() {
         if neigh == target {
           return Ok(t + 1);
         }
-        if ok(neigh) && !board.occupied(neigh.0, neigh.1) {
+        if ok(neigh) && !board.occupied(neigh.x, neigh.y) {
           new_frontier.push(neigh)
         }
       }
-      if !board.occupied(x, y) {
-        new_frontier.push((x, y))
+      if !board.occupied(p.x, p.y) {
+        new_frontier.push(p)
       }
     }
     new_frontier.sort();
@@ -116,8 +116,8 @@ fn shortest(start_t: i32, start: (i32, i32), target: (i32, i32), board: &Board)
 }
 
 fn solve(board: &Board) -> AocResult<(i32, i32)> {
-  let start = (1, 0);
-  let target = (board.width - 2, board.height - 1);
+  let start = Point::new(1, 0);
+  let target = Point::new(board.width - 2, board.height - 1);
 
   let t1 = shortest(0, start, target, board)?;
   let t2 = shortest(t1, target, start, board)?;
@@ -125,4 +125,5 @@ fn solve(board: &Board) -> AocResult<(i32, i32)> {
   Ok((t1, t3))
 }
 
+#[aoc::solution(day 24: 30 runs, expected 242 720, tags(slow))]
 pub fn run(input: &Board) -> AocResult<(i32, i32)> { solve(input) }
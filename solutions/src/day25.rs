@@ -93,6 +93,7 @@ fn part2(_input: &[Vec<u8>]) -> AocResult<String> {
   Ok("Done!".to_string())
 }
 
+#[aoc::solution(day 25: 1000 runs, expected "2-20=01--0=0=0=2-120" "Done!")]
 pub fn run(input: &[Vec<u8>]) -> AocResult<(String, String)> {
   Ok((part1(input)?, part2(input)?))
 }
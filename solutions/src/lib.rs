@@ -1,5 +1,5 @@
 #![feature(iter_array_chunks)]
-#![feature(array_chunks)]
+// COMPAT
 #![feature(array_windows)]
 //#![feature(generic_const_exprs)]
 #![feature(portable_simd)]
@@ -7,6 +7,7 @@
 #![feature(map_try_insert)]
 #![feature(int_roundings)]
 
+pub mod samples;
 pub mod utils;
 
 pub mod day01;
@@ -0,0 +1,78 @@
+// Example inputs from the puzzle texts and their answers, compiled in so any
+// day can be sanity checked without input files.
+//
+// Missing day: 22 (the cube folding only handles the layout of the real
+// input).
+
+use error::AocResult;
+
+/// Takes the input file name and returns both answers.
+pub type SampleRun = fn(&str) -> AocResult<(String, String)>;
+
+pub struct Sample {
+  pub day: u32,
+  pub input: &'static str,
+  pub part1: &'static str,
+  pub part2: &'static str,
+  /// Solves the example when it needs other parameters than the real input,
+  /// in place of the registered solution.
+  pub run: Option<SampleRun>,
+}
+
+macro_rules! sample {
+  ($day: literal, $file: literal, $part1: literal, $part2: literal) => {
+    Sample {
+      day: $day,
+      input: include_str!(concat!("../samples/", $file)),
+      part1: $part1,
+      part2: $part2,
+      run: None,
+    }
+  };
+  ($day: literal, $file: literal, $part1: literal, $part2: literal, run $run: expr) => {
+    Sample { run: Some($run), ..sample!($day, $file, $part1, $part2) }
+  };
+}
+
+pub const SAMPLES: &[Sample] = &[
+  sample!(1, "01.txt", "24000", "45000"),
+  sample!(2, "02.txt", "15", "12"),
+  sample!(3, "03.txt", "157", "70"),
+  sample!(4, "04.txt", "2", "4"),
+  sample!(5, "05.txt", "CMZ", "MCD"),
+  sample!(6, "06.txt", "7", "19"),
+  sample!(7, "07.txt", "95437", "24933642"),
+  sample!(8, "08.txt", "21", "8"),
+  sample!(9, "09.txt", "13", "1"),
+  sample!(
+    10,
+    "10.txt",
+    "13140",
+    "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."
+  ),
+  sample!(11, "11.txt", "10605", "2713310158"),
+  sample!(12, "12.txt", "31", "29"),
+  sample!(13, "13.txt", "13", "140"),
+  sample!(14, "14.txt", "24", "93"),
+  // Row 10 and a 20 wide area instead of 2000000 and 4000000.
+  sample!(15, "15.txt", "26", "56000011", run |fname| {
+    let (part1, part2) = crate::day15::solve(&crate::day15::parse_input(fname)?, 10, 20)?;
+    Ok((part1.to_string(), part2.to_string()))
+  }),
+  sample!(16, "16.txt", "1651", "1707"),
+  sample!(17, "17.txt", "3068", "1514285714288"),
+  sample!(18, "18.txt", "64", "58"),
+  sample!(19, "19.txt", "33", "3472"),
+  sample!(20, "20.txt", "3", "1623178306"),
+  sample!(21, "21.txt", "152", "301"),
+  sample!(23, "23.txt", "110", "20"),
+  sample!(24, "24.txt", "18", "54"),
+  sample!(25, "25.txt", "2=-1=0", "Done!"),
+];
+
+pub fn sample(day: u32) -> Option<&'static Sample> { SAMPLES.iter().find(|s| s.day == day) }
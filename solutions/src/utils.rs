@@ -1,71 +1,220 @@
-use error::AocResult;
+use std::simd::cmp::{SimdPartialEq, SimdPartialOrd};
+use std::simd::num::SimdUint;
+use std::simd::{simd_swizzle, u16x8, u32x4, u64x2, u8x16, u8x64};
 
-pub fn num_from_bytes<T: num::PrimInt>(bytes: &[u8]) -> T {
-  let mut n = 0;
-  for c in bytes {
-    n = n * 10 + (c - b'0') as i32;
+use bstr::ByteSlice;
+
+use error::{aoc_error, aoc_error_msg, AocResult};
+
+pub mod bits;
+pub mod cycle;
+pub mod grid;
+pub mod interval;
+pub mod parser;
+pub mod point;
+pub mod search;
+
+/// Parses a run of ASCII digits, failing on anything else or on overflow.
+pub fn num_from_bytes<T: num::PrimInt>(bytes: &[u8]) -> AocResult<T> {
+  if bytes.is_empty() || !bytes.iter().all(u8::is_ascii_digit) {
+    return aoc_error(&format!("Not a number: {:?}", bytes.as_bstr()));
   }
+  to_num(digits_value(bytes), false, bytes)
+}
 
-  let res = unsafe { T::from(n).unwrap_unchecked() };
-  res
+/// Value of a run of ASCII digits, `None` on overflow.
+fn digits_value(digits: &[u8]) -> Option<u128> {
+  digits.iter().try_fold(0u128, |n, c| n.checked_mul(10)?.checked_add((c - b'0') as u128))
 }
-pub fn read_all_nums_from_bytes<T: num::PrimInt>(bytes: &[u8]) -> AocResult<Vec<T>> {
+
+fn to_num<T: num::PrimInt>(n: Option<u128>, negative: bool, text: &[u8]) -> AocResult<T> {
+  let n = match (n, negative) {
+    (Some(n), false) => T::from(n),
+    (Some(n), true) => i128::try_from(n).ok().and_then(|n| T::from(-n)),
+    (None, _) => None,
+  };
+  n.ok_or_else(|| aoc_error_msg(&format!("Number out of range: {:?}", text.as_bstr())))
+}
+
+// Scalar versions of the number readers. Kept around as the reference the
+// SIMD versions below are checked against.
+
+pub fn read_all_nums_from_bytes_scalar<T: num::PrimInt>(bytes: &[u8]) -> AocResult<Vec<T>> {
   let mut res = vec![];
 
-  let mut n = 0;
-  let mut reading = false;
-  for c in bytes {
-    if reading {
-      if c.is_ascii_digit() {
-        n = n * 10 + (c - b'0') as i32;
-      } else {
-        res.push(unsafe { T::from(n).unwrap_unchecked() });
-        reading = false;
-      }
-    } else {
-      if c.is_ascii_digit() {
-        n = (c - b'0') as i32;
-        reading = true;
+  let mut start = None;
+  for (i, c) in bytes.iter().enumerate() {
+    match (start, c.is_ascii_digit()) {
+      (None, true) => start = Some(i),
+      (Some(s), false) => {
+        res.push(to_num(digits_value(&bytes[s..i]), false, &bytes[s..i])?);
+        start = None;
       }
+      _ => {}
     }
   }
-  if reading {
-    res.push(unsafe { T::from(n).unwrap_unchecked() });
+  if let Some(s) = start {
+    res.push(to_num(digits_value(&bytes[s..]), false, &bytes[s..])?);
   }
 
   Ok(res)
 }
 
-pub fn read_all_signed_nums_from_bytes<T: num::PrimInt>(bytes: &[u8]) -> AocResult<Vec<T>> {
+/// A `-` starts a negative number, so a lone one reads as 0. The character
+/// ending a number is not looked at again, so `--5` reads as 0 and 5.
+pub fn read_all_signed_nums_from_bytes_scalar<T: num::PrimInt>(
+  bytes: &[u8],
+) -> AocResult<Vec<T>> {
   let mut res = vec![];
 
-  let mut n = 0i32;
-  let mut negative = false;
-  let mut reading = false;
-  for c in bytes {
-    if reading {
-      if c.is_ascii_digit() {
-        n = n * 10 + (c - b'0') as i32;
-      } else {
-        res.push(unsafe { T::from(if negative { -n } else { n }).unwrap_unchecked() });
-        reading = false;
+  // Start of the number being read, including its sign.
+  let mut start = None;
+  let mut push = |number: &[u8]| -> AocResult<()> {
+    let (negative, digits) = match number.split_first() {
+      Some((b'-', digits)) => (true, digits),
+      _ => (false, number),
+    };
+    res.push(to_num(digits_value(digits), negative, number)?);
+    Ok(())
+  };
+  for (i, c) in bytes.iter().enumerate() {
+    match start {
+      Some(s) if !c.is_ascii_digit() => {
+        push(&bytes[s..i])?;
+        start = None;
       }
-    } else {
-      if c.is_ascii_digit() {
-        n = (c - b'0') as i32;
-        negative = false;
-        reading = true;
-      } else if *c == b'-' {
-        n = 0;
-        negative = true;
-        reading = true;
+      None if c.is_ascii_digit() || *c == b'-' => start = Some(i),
+      _ => {}
+    }
+  }
+  if let Some(s) = start {
+    push(&bytes[s..])?;
+  }
+
+  Ok(res)
+}
+
+// SIMD versions. Digits are located 64 bytes at a time using a bitmask, and
+// each run of up to 16 digits is converted with a few multiply-adds.
+
+const BLOCK: usize = 64;
+
+fn digit_mask(block: &[u8]) -> u64 {
+  let v = u8x64::from_slice(block) - u8x64::splat(b'0');
+  v.simd_lt(u8x64::splat(10)).to_bitmask()
+}
+
+/// Digits and `-`, the characters a signed number is made of.
+fn signed_mask(block: &[u8]) -> u64 {
+  digit_mask(block) | u8x64::from_slice(block).simd_eq(u8x64::splat(b'-')).to_bitmask()
+}
+
+/// Calls `f(start, end)` for every maximal run of bytes matching `in_run`,
+/// with `mask` giving the same per byte for a whole block.
+fn for_each_run(
+  bytes: &[u8],
+  in_run: impl Fn(u8) -> bool,
+  mask: impl Fn(&[u8]) -> u64,
+  mut f: impl FnMut(usize, usize) -> AocResult<()>,
+) -> AocResult<()> {
+  let n = bytes.len();
+  let mut i = 0;
+  while i + BLOCK <= n {
+    let mut mask = mask(&bytes[i..i + BLOCK]);
+    let mut consumed = BLOCK;
+    while mask != 0 {
+      let start = mask.trailing_zeros() as usize;
+      let len = (mask >> start).trailing_ones() as usize;
+      let end = start + len;
+      if end == BLOCK {
+        if start != 0 {
+          // Run may continue in the next block, restart from its beginning.
+          consumed = start;
+          break;
+        }
+        // Absurdly long run, finish it off by hand.
+        let mut e = i + end;
+        while e < n && in_run(bytes[e]) {
+          e += 1;
+        }
+        f(i, e)?;
+        consumed = e - i;
+        break;
       }
+      f(i + start, i + end)?;
+      mask &= !0 << end;
     }
+    i += consumed;
   }
-  if reading {
-    res.push(unsafe { T::from(if negative { -n } else { n }).unwrap_unchecked() });
+
+  // Scalar tail.
+  while i < n {
+    if in_run(bytes[i]) {
+      let start = i;
+      while i < n && in_run(bytes[i]) {
+        i += 1;
+      }
+      f(start, i)?;
+    } else {
+      i += 1;
+    }
   }
+  Ok(())
+}
+
+/// Converts a run of ASCII digits to a number, 16 digits at a time. `None` on
+/// overflow.
+fn parse_digits(digits: &[u8]) -> Option<u128> {
+  if digits.len() > 16 {
+    return digits_value(digits);
+  }
+
+  // Right align with zero padding, most significant digit in lane 0.
+  let mut buf = [b'0'; 16];
+  buf[16 - digits.len()..].copy_from_slice(digits);
+  let v = u8x16::from_array(buf) - u8x16::splat(b'0');
 
+  let hi: u16x8 = simd_swizzle!(v, [0, 2, 4, 6, 8, 10, 12, 14]).cast();
+  let lo: u16x8 = simd_swizzle!(v, [1, 3, 5, 7, 9, 11, 13, 15]).cast();
+  let v = hi * u16x8::splat(10) + lo;
+
+  let hi: u32x4 = simd_swizzle!(v, [0, 2, 4, 6]).cast();
+  let lo: u32x4 = simd_swizzle!(v, [1, 3, 5, 7]).cast();
+  let v = hi * u32x4::splat(100) + lo;
+
+  let hi: u64x2 = simd_swizzle!(v, [0, 2]).cast();
+  let lo: u64x2 = simd_swizzle!(v, [1, 3]).cast();
+  let v = hi * u64x2::splat(10000) + lo;
+
+  Some((v[0] * 100_000_000 + v[1]) as u128)
+}
+
+pub fn read_all_nums_from_bytes<T: num::PrimInt>(bytes: &[u8]) -> AocResult<Vec<T>> {
+  let mut res = vec![];
+  for_each_run(bytes, |c| c.is_ascii_digit(), digit_mask, |start, end| {
+    let digits = &bytes[start..end];
+    res.push(to_num(parse_digits(digits), false, digits)?);
+    Ok(())
+  })?;
+  Ok(res)
+}
+
+pub fn read_all_signed_nums_from_bytes<T: num::PrimInt>(bytes: &[u8]) -> AocResult<Vec<T>> {
+  let mut res = vec![];
+  for_each_run(bytes, |c| c.is_ascii_digit() || c == b'-', signed_mask, |start, end| {
+    let run = &bytes[start..end];
+    let (negative, digits) = match run.split_first() {
+      Some((b'-', digits)) => (true, digits),
+      _ => (false, run),
+    };
+    if digits.contains(&b'-') {
+      // Several numbers, or stray signs, run together.
+      res.extend(read_all_signed_nums_from_bytes_scalar::<T>(run)?);
+    } else {
+      res.push(to_num(parse_digits(digits), negative, run)?);
+    }
+    Ok(())
+  })?;
   Ok(res)
 }
 
@@ -78,3 +227,89 @@ pub fn read_all_signed_nums<T: num::PrimInt>(fname: &str) -> AocResult<Vec<T>> {
   let contents = std::fs::read(fname)?;
   read_all_signed_nums_from_bytes(&contents)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn both<T: num::PrimInt + std::fmt::Debug>(bytes: &[u8]) -> (Vec<T>, Vec<T>) {
+    let unsigned = read_all_nums_from_bytes(bytes).unwrap();
+    assert_eq!(unsigned, read_all_nums_from_bytes_scalar(bytes).unwrap(), "{:?}", bytes.as_bstr());
+    let signed = read_all_signed_nums_from_bytes(bytes).unwrap();
+    let scalar = read_all_signed_nums_from_bytes_scalar(bytes).unwrap();
+    assert_eq!(signed, scalar, "{:?}", bytes.as_bstr());
+    (unsigned, signed)
+  }
+
+  #[test]
+  fn signs() {
+    assert_eq!(both::<i64>(b"-"), (vec![], vec![0]));
+    assert_eq!(both::<i64>(b"--5"), (vec![5], vec![0, 5]));
+    assert_eq!(both::<i64>(b"---5"), (vec![5], vec![0, -5]));
+    assert_eq!(both::<i64>(b"3-4"), (vec![3, 4], vec![3, 4]));
+    assert_eq!(both::<i64>(b"x=-7, y=8"), (vec![7, 8], vec![-7, 8]));
+    assert_eq!(both::<i64>(b",,12,,-"), (vec![12], vec![12, 0]));
+    assert_eq!(both::<i64>(b"\n-1\n2\n"), (vec![1, 2], vec![-1, 2]));
+    assert_eq!(both::<i64>(b""), (vec![], vec![]));
+  }
+
+  #[test]
+  fn long_runs() {
+    let n = format!("{}{}", "0".repeat(70), "123456789012345678");
+    assert_eq!(both::<u64>(n.as_bytes()).0, vec![123456789012345678]);
+    let n = "12345678901234567890123";
+    assert_eq!(both::<u128>(n.as_bytes()).0, vec![12345678901234567890123]);
+    assert_eq!(both::<i128>(format!("a-{}", n).as_bytes()).1, vec![-12345678901234567890123]);
+  }
+
+  #[test]
+  fn block_boundaries() {
+    // Numbers of every length straddling the 64 byte blocks at every offset.
+    let mut seed = 12345u64;
+    let mut next = |m: u64| {
+      seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+      (seed >> 33) % m
+    };
+    for _ in 0..500 {
+      let mut bytes = vec![];
+      while bytes.len() < 300 {
+        match next(6) {
+          0 => bytes.push(b'-'),
+          1 | 2 => bytes.extend(b" ,\n:x".get(next(5) as usize)),
+          _ => {
+            (0..1 + next(18)).for_each(|_| bytes.push(b'0' + next(10) as u8));
+            bytes.extend(b" -".get(next(2) as usize));
+          }
+        }
+      }
+      both::<i64>(&bytes);
+    }
+  }
+
+  #[test]
+  fn out_of_range() {
+    for f in [read_all_nums_from_bytes::<u8>, read_all_nums_from_bytes_scalar::<u8>] {
+      assert_eq!(f(b"1 255").unwrap(), vec![1, 255]);
+      assert!(f(b"1 256").is_err());
+    }
+    for f in [read_all_signed_nums_from_bytes::<i8>, read_all_signed_nums_from_bytes_scalar::<i8>] {
+      assert_eq!(f(b"-128 127").unwrap(), vec![-128, 127]);
+      assert!(f(b"-129").is_err());
+      assert!(f(b"128").is_err());
+    }
+    for f in [read_all_nums_from_bytes::<u64>, read_all_nums_from_bytes_scalar::<u64>] {
+      assert!(f(b"18446744073709551616").is_err());
+      assert!(f("9".repeat(60).as_bytes()).is_err());
+    }
+    assert!(read_all_signed_nums_from_bytes::<u32>(b"-1").is_err());
+  }
+
+  #[test]
+  fn single_number() {
+    assert_eq!(num_from_bytes::<i32>(b"2147483647").unwrap(), i32::MAX);
+    assert!(num_from_bytes::<i32>(b"2147483648").is_err());
+    assert!(num_from_bytes::<i32>(b"12a").is_err());
+    assert!(num_from_bytes::<i32>(b"-1").is_err());
+    assert!(num_from_bytes::<i32>(b"").is_err());
+  }
+}
@@ -0,0 +1,326 @@
+use std::ops::{BitAnd, BitOr, BitXor, Not, Sub};
+
+use num::PrimInt;
+
+// Set of small integers packed into a single primitive integer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bits<T>(pub T);
+
+impl<T: PrimInt> Bits<T> {
+  pub fn empty() -> Self { Bits(T::zero()) }
+
+  /// The set {0, 1, ..., n - 1}.
+  pub fn full(n: usize) -> Self {
+    if n >= Self::capacity() {
+      Bits(!T::zero())
+    } else {
+      Bits((T::one() << n) - T::one())
+    }
+  }
+
+  pub fn capacity() -> usize { T::zero().count_zeros() as usize }
+
+  /// Out of range elements are never contained.
+  pub fn contains(self, i: usize) -> bool {
+    i < Self::capacity() && self.0 >> i & T::one() == T::one()
+  }
+  pub fn insert(&mut self, i: usize) { *self = self.with(i); }
+  pub fn remove(&mut self, i: usize) {
+    if i < Self::capacity() {
+      self.0 = self.0 & !(T::one() << i);
+    }
+  }
+  pub fn with(self, i: usize) -> Self {
+    assert!(i < Self::capacity(), "{} out of bounds for {} bits", i, Self::capacity());
+    Bits(self.0 | T::one() << i)
+  }
+
+  pub fn len(self) -> usize { self.0.count_ones() as usize }
+  pub fn is_empty(self) -> bool { self.0.is_zero() }
+
+  pub fn is_subset(self, other: Self) -> bool { self & other == self }
+
+  /// Elements of {0, ..., n - 1} not in the set.
+  pub fn complement(self, n: usize) -> Self { !self & Self::full(n) }
+
+  /// Indices of set bits in increasing order.
+  pub fn iter(self) -> impl Iterator<Item = usize> {
+    let mut n = self.0;
+    std::iter::from_fn(move || {
+      if n.is_zero() {
+        return None;
+      }
+      let i = n.trailing_zeros() as usize;
+      n = n & (n - T::one());
+      Some(i)
+    })
+  }
+
+  /// All subsets, from the set itself down to the empty set.
+  pub fn subsets(self) -> impl Iterator<Item = Self> {
+    let m = self.0;
+    let mut next = Some(m);
+    std::iter::from_fn(move || {
+      let x = next?;
+      next = (!x.is_zero()).then(|| (x - T::one()) & m);
+      Some(Bits(x))
+    })
+  }
+}
+
+impl<T: PrimInt> BitOr for Bits<T> {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self::Output { Bits(self.0 | rhs.0) }
+}
+
+impl<T: PrimInt> BitAnd for Bits<T> {
+  type Output = Self;
+
+  fn bitand(self, rhs: Self) -> Self::Output { Bits(self.0 & rhs.0) }
+}
+
+impl<T: PrimInt> BitXor for Bits<T> {
+  type Output = Self;
+
+  fn bitxor(self, rhs: Self) -> Self::Output { Bits(self.0 ^ rhs.0) }
+}
+
+impl<T: PrimInt> Sub for Bits<T> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self::Output { Bits(self.0 & !rhs.0) }
+}
+
+impl<T: PrimInt> Not for Bits<T> {
+  type Output = Self;
+
+  fn not(self) -> Self::Output { Bits(!self.0) }
+}
+
+impl<T: PrimInt> FromIterator<usize> for Bits<T> {
+  fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+    iter.into_iter().fold(Bits::empty(), Bits::with)
+  }
+}
+
+// Dynamically sized bitset.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+  words: Vec<u64>,
+  size: usize,
+}
+
+impl BitSet {
+  /// Empty set able to hold {0, ..., size - 1}.
+  pub fn new(size: usize) -> Self { BitSet { words: vec![0; size.div_ceil(64)], size } }
+
+  pub fn capacity(&self) -> usize { self.size }
+
+  pub fn contains(&self, i: usize) -> bool {
+    i < self.size && self.words[i / 64] >> (i % 64) & 1 == 1
+  }
+
+  /// Inserts `i`, returning whether it was newly added.
+  pub fn insert(&mut self, i: usize) -> bool {
+    assert!(i < self.size, "{} out of bounds for bitset of size {}", i, self.size);
+    let w = &mut self.words[i / 64];
+    let new = *w >> (i % 64) & 1 == 0;
+    *w |= 1 << (i % 64);
+    new
+  }
+
+  pub fn remove(&mut self, i: usize) {
+    if i < self.size {
+      self.words[i / 64] &= !(1 << (i % 64));
+    }
+  }
+
+  pub fn set(&mut self, i: usize, value: bool) {
+    if value {
+      self.insert(i);
+    } else {
+      self.remove(i);
+    }
+  }
+
+  pub fn clear(&mut self) { self.words.fill(0); }
+
+  pub fn len(&self) -> usize { self.words.iter().map(|w| w.count_ones() as usize).sum() }
+  pub fn is_empty(&self) -> bool { self.words.iter().all(|&w| w == 0) }
+
+  pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+    self
+      .words
+      .iter()
+      .enumerate()
+      .flat_map(|(i, &w)| Bits(w).iter().map(move |j| 64 * i + j))
+  }
+
+  fn zip_with(&mut self, other: &BitSet, f: impl Fn(u64, u64) -> u64) {
+    assert_eq!(self.size, other.size, "bitsets of different sizes");
+    for (a, &b) in self.words.iter_mut().zip(&other.words) {
+      *a = f(*a, b);
+    }
+  }
+
+  pub fn union_with(&mut self, other: &BitSet) { self.zip_with(other, |a, b| a | b) }
+  pub fn intersect_with(&mut self, other: &BitSet) { self.zip_with(other, |a, b| a & b) }
+  pub fn difference_with(&mut self, other: &BitSet) { self.zip_with(other, |a, b| a & !b) }
+
+  pub fn is_subset(&self, other: &BitSet) -> bool {
+    self.words.iter().zip(&other.words).all(|(&a, &b)| a & !b == 0)
+  }
+}
+
+// 2D and 3D grids of bits. Out of bounds reads are false.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid2 {
+  bits: BitSet,
+  width: usize,
+  height: usize,
+}
+
+impl BitGrid2 {
+  pub fn new(width: usize, height: usize) -> Self {
+    BitGrid2 { bits: BitSet::new(width * height), width, height }
+  }
+
+  pub fn width(&self) -> usize { self.width }
+  pub fn height(&self) -> usize { self.height }
+
+  pub fn in_bounds(&self, x: usize, y: usize) -> bool { x < self.width && y < self.height }
+
+  pub fn get(&self, x: usize, y: usize) -> bool {
+    self.in_bounds(x, y) && self.bits.contains(y * self.width + x)
+  }
+
+  pub fn set(&mut self, x: usize, y: usize, value: bool) {
+    assert!(self.in_bounds(x, y), "({}, {}) out of bounds", x, y);
+    self.bits.set(y * self.width + x, value);
+  }
+
+  pub fn count(&self) -> usize { self.bits.len() }
+
+  pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+    self.bits.iter().map(|i| (i % self.width, i / self.width))
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid3 {
+  bits: BitSet,
+  dims: (usize, usize, usize),
+}
+
+impl BitGrid3 {
+  pub fn new(nx: usize, ny: usize, nz: usize) -> Self {
+    BitGrid3 { bits: BitSet::new(nx * ny * nz), dims: (nx, ny, nz) }
+  }
+
+  pub fn dims(&self) -> (usize, usize, usize) { self.dims }
+
+  pub fn in_bounds(&self, x: usize, y: usize, z: usize) -> bool {
+    let (nx, ny, nz) = self.dims;
+    x < nx && y < ny && z < nz
+  }
+
+  fn ix(&self, x: usize, y: usize, z: usize) -> usize {
+    let (nx, ny, _) = self.dims;
+    (z * ny + y) * nx + x
+  }
+
+  pub fn get(&self, x: usize, y: usize, z: usize) -> bool {
+    self.in_bounds(x, y, z) && self.bits.contains(self.ix(x, y, z))
+  }
+
+  pub fn set(&mut self, x: usize, y: usize, z: usize, value: bool) {
+    assert!(self.in_bounds(x, y, z), "({}, {}, {}) out of bounds", x, y, z);
+    let ix = self.ix(x, y, z);
+    self.bits.set(ix, value);
+  }
+
+  pub fn count(&self) -> usize { self.bits.len() }
+
+  pub fn iter(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    let (nx, ny, _) = self.dims;
+    self.bits.iter().map(move |i| (i % nx, i / nx % ny, i / (nx * ny)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bits() {
+    let mut b: Bits<u8> = [1, 3, 7].into_iter().collect();
+    assert_eq!(b.0, 0b1000_1010);
+    assert!(b.contains(7) && !b.contains(2));
+    assert!(!b.contains(8) && !b.contains(1000));
+    b.remove(3);
+    b.remove(8);
+    b.insert(0);
+    assert_eq!(b.iter().collect::<Vec<_>>(), vec![0, 1, 7]);
+    assert_eq!((b.len(), b.is_empty(), Bits::<u8>::empty().is_empty()), (3, false, true));
+    assert_eq!(Bits::<u8>::full(3).0, 0b111);
+    assert_eq!(Bits::<u8>::full(8).0, 0xff);
+    assert_eq!(Bits::<u8>(0b0101).complement(4).0, 0b1010);
+    assert!(Bits::<u8>(0b0100).is_subset(Bits(0b0110)));
+    assert_eq!((Bits::<u8>(0b0110) - Bits(0b0011)).0, 0b0100);
+  }
+
+  #[test]
+  #[should_panic(expected = "out of bounds")]
+  fn bits_insert_out_of_bounds() {
+    let mut b = Bits::<u16>::empty();
+    b.insert(16);
+  }
+
+  #[test]
+  fn subsets() {
+    let subsets = Bits::<u8>(0b1011).subsets().map(|s| s.0).collect::<Vec<_>>();
+    assert_eq!(subsets, vec![0b1011, 0b1010, 0b1001, 0b1000, 0b0011, 0b0010, 0b0001, 0]);
+    assert_eq!(Bits::<u8>::empty().subsets().count(), 1);
+    assert_eq!(Bits::<u32>::full(10).subsets().count(), 1024);
+  }
+
+  #[test]
+  fn bitset() {
+    let mut a = BitSet::new(130);
+    assert!(a.insert(0) && a.insert(64) && a.insert(129));
+    assert!(!a.insert(64));
+    assert!(a.contains(129) && !a.contains(130) && !a.contains(1));
+    a.remove(1000);
+    assert_eq!(a.iter().collect::<Vec<_>>(), vec![0, 64, 129]);
+
+    let mut b = BitSet::new(130);
+    b.set(64, true);
+    b.set(65, true);
+    assert!(!b.is_subset(&a));
+    b.set(65, false);
+    assert!(b.is_subset(&a));
+    a.difference_with(&b);
+    assert_eq!((a.len(), a.contains(64)), (2, false));
+    a.union_with(&b);
+    a.intersect_with(&b);
+    assert_eq!(a.iter().collect::<Vec<_>>(), vec![64]);
+    a.clear();
+    assert!(a.is_empty());
+  }
+
+  #[test]
+  fn grids() {
+    let mut g = BitGrid2::new(3, 2);
+    g.set(2, 1, true);
+    assert!(g.get(2, 1) && !g.get(1, 2) && !g.get(3, 0));
+    assert_eq!(g.iter().collect::<Vec<_>>(), vec![(2, 1)]);
+
+    let mut g = BitGrid3::new(2, 3, 4);
+    g.set(1, 2, 3, true);
+    g.set(0, 1, 0, true);
+    assert!(g.get(1, 2, 3) && !g.get(2, 0, 0) && !g.get(0, 0, 4));
+    assert_eq!(g.iter().collect::<Vec<_>>(), vec![(0, 1, 0), (1, 2, 3)]);
+    assert_eq!(g.count(), 2);
+  }
+}
@@ -0,0 +1,181 @@
+use std::hash::Hash;
+
+use ahash::AHashMap;
+
+// Cycle detection for sequences x, f(x), f(f(x)), ...
+//
+// Floyd and Brent need the states themselves to be cheap to clone and compare.
+// For simulations with large states, `extrapolate` instead takes a key
+// summarizing each state and remembers when it was seen.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+  /// First step that is part of the cycle.
+  pub start: usize,
+  pub len: usize,
+}
+
+impl Cycle {
+  /// Earliest step with the same state as `step`.
+  pub fn reduce(&self, step: usize) -> usize {
+    if step < self.start {
+      step
+    } else {
+      self.start + (step - self.start) % self.len
+    }
+  }
+
+  /// Extends a metric recorded per step (`metrics[i]` after `i` steps) to
+  /// `step`, assuming it changes by the same amount every cycle. `metrics`
+  /// must cover one full cycle.
+  pub fn extrapolate(&self, metrics: &[i64], step: usize) -> i64 {
+    if step < metrics.len() {
+      return metrics[step];
+    }
+    let per_cycle = metrics[self.start + self.len] - metrics[self.start];
+    let cycles = ((step - self.start) / self.len) as i64;
+    metrics[self.reduce(step)] + cycles * per_cycle
+  }
+}
+
+pub fn floyd<T: Clone + PartialEq>(x0: T, mut f: impl FnMut(&T) -> T) -> Cycle {
+  // Find some multiple of the cycle length.
+  let mut slow = f(&x0);
+  let mut fast = f(&slow);
+  while slow != fast {
+    slow = f(&slow);
+    fast = f(&fast);
+    fast = f(&fast);
+  }
+
+  // Distance to the cycle start is the same from x0 and from the meeting point.
+  let mut start = 0;
+  slow = x0;
+  while slow != fast {
+    slow = f(&slow);
+    fast = f(&fast);
+    start += 1;
+  }
+
+  let mut len = 1;
+  fast = f(&slow);
+  while slow != fast {
+    fast = f(&fast);
+    len += 1;
+  }
+  Cycle { start, len }
+}
+
+pub fn brent<T: Clone + PartialEq>(x0: T, mut f: impl FnMut(&T) -> T) -> Cycle {
+  // Search for the length in power of two sized windows.
+  let mut power = 1;
+  let mut len = 1;
+  let mut slow = x0.clone();
+  let mut fast = f(&x0);
+  while slow != fast {
+    if power == len {
+      slow = fast.clone();
+      power *= 2;
+      len = 0;
+    }
+    fast = f(&fast);
+    len += 1;
+  }
+
+  // Walk two pointers `len` apart until they meet at the cycle start.
+  let mut start = 0;
+  slow = x0.clone();
+  fast = x0;
+  for _ in 0..len {
+    fast = f(&fast);
+  }
+  while slow != fast {
+    slow = f(&slow);
+    fast = f(&fast);
+    start += 1;
+  }
+  Cycle { start, len }
+}
+
+/// Runs a simulation until a state key repeats and extrapolates its metric to
+/// `target` steps. `step` advances the simulation once and returns the key and
+/// metric of the new state, `initial` is the metric before any steps.
+pub fn extrapolate<K: Hash + Eq>(
+  target: usize,
+  initial: i64,
+  mut step: impl FnMut() -> (K, i64),
+) -> i64 {
+  let mut seen = AHashMap::new();
+  let mut metrics = vec![initial];
+  while metrics.len() <= target {
+    let (key, metric) = step();
+    metrics.push(metric);
+    let i = metrics.len() - 1;
+    if let Some(&start) = seen.get(&key) {
+      return Cycle { start, len: i - start }.extrapolate(&metrics, target);
+    }
+    seen.insert(key, i);
+  }
+  metrics[target]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // x -> x^2 + 1 mod 255 starting at 3: 3, 10, 101, 2, 5, 26, 167, 95, 101, ...
+  fn step(x: &u32) -> u32 { (x * x + 1) % 255 }
+
+  #[test]
+  fn floyd_and_brent() {
+    let expected = Cycle { start: 2, len: 6 };
+    assert_eq!(floyd(3, step), expected);
+    assert_eq!(brent(3, step), expected);
+    // Pure cycles and fixed points.
+    assert_eq!(floyd(0, |x| (x + 1) % 7), Cycle { start: 0, len: 7 });
+    assert_eq!(brent(0, |x| (x + 1) % 7), Cycle { start: 0, len: 7 });
+    assert_eq!(floyd(4, |_| 4), Cycle { start: 0, len: 1 });
+    assert_eq!(brent(4, |_| 4), Cycle { start: 0, len: 1 });
+    assert_eq!(brent(9, |_| 4), Cycle { start: 1, len: 1 });
+  }
+
+  #[test]
+  fn floyd_and_brent_agree() {
+    for x0 in 0..100 {
+      for m in [97, 100, 256] {
+        let f = |x: &u64| (x * x + 3) % m;
+        assert_eq!(floyd(x0, f), brent(x0, f), "x0 = {}, m = {}", x0, m);
+      }
+    }
+  }
+
+  #[test]
+  fn reduce() {
+    let c = Cycle { start: 2, len: 3 };
+    assert_eq!((0..10).map(|i| c.reduce(i)).collect::<Vec<_>>(), [0, 1, 2, 3, 4, 2, 3, 4, 2, 3]);
+  }
+
+  #[test]
+  fn extrapolate_metric() {
+    // Steps 0 and 1 lead into a cycle of 3 that adds 10 each time around.
+    let metrics = [0, 1, 5, 7, 12, 15];
+    let c = Cycle { start: 2, len: 3 };
+    assert_eq!(c.extrapolate(&metrics, 4), 12);
+    assert_eq!(c.extrapolate(&metrics, 5), 15);
+    assert_eq!(c.extrapolate(&metrics, 6), 17);
+    assert_eq!(c.extrapolate(&metrics, 2 + 3 * 1000), 5 + 10 * 1000);
+
+    // Keys 0, 1, 2, 3, 1, 2, 3, ..., each step adding one more than its key.
+    let key = |i: usize| if i == 0 { 0 } else { 1 + (i - 1) % 3 };
+    let height = |n: usize| (0..n).map(|i| key(i) as i64 + 1).sum::<i64>();
+    for target in (0..30).chain([1000, 12345]) {
+      let mut i = 0;
+      let res = extrapolate(target, 0, || {
+        i += 1;
+        (key(i - 1), height(i))
+      });
+      assert_eq!(res, height(target), "target {}", target);
+    }
+    assert_eq!(extrapolate(0, 42, || (0, 0)), 42);
+  }
+}
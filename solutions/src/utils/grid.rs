@@ -0,0 +1,293 @@
+use std::fmt::{self, Display};
+use std::iter::StepBy;
+use std::ops::{Index, IndexMut};
+use std::slice::{ChunksExact, Iter};
+
+use bstr::ByteSlice;
+use itertools::Itertools;
+
+use error::{aoc_error, AocResult};
+use super::point::Point2;
+
+// Row-major 2D grid, indexed by (x, y) with x being the column.
+//
+// Coordinates are usize. Signed coordinates can be cast with `as usize`,
+// negative ones wrap around to something huge and are reported as out of
+// bounds by `get`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+  data: Vec<T>,
+  width: usize,
+  height: usize,
+}
+
+const OFFSETS4: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const OFFSETS8: [(isize, isize); 8] = [
+  (1, 0),
+  (1, 1),
+  (0, 1),
+  (-1, 1),
+  (-1, 0),
+  (-1, -1),
+  (0, -1),
+  (1, -1),
+];
+
+impl<T> Grid<T> {
+  pub fn from_vec(width: usize, height: usize, data: Vec<T>) -> AocResult<Self> {
+    if data.len() != width * height {
+      return aoc_error(&format!(
+        "Grid of size {}x{} can't hold {} elements",
+        width,
+        height,
+        data.len()
+      ));
+    }
+    Ok(Grid { data, width, height })
+  }
+
+  pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+    let data = (0..height)
+      .flat_map(|y| (0..width).map(move |x| (x, y)))
+      .map(|(x, y)| f(x, y))
+      .collect_vec();
+    Grid { data, width, height }
+  }
+
+  pub fn width(&self) -> usize { self.width }
+  pub fn height(&self) -> usize { self.height }
+
+  pub fn in_bounds(&self, x: usize, y: usize) -> bool { x < self.width && y < self.height }
+
+  fn ix(&self, x: usize, y: usize) -> usize { y * self.width + x }
+
+  pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+    if self.in_bounds(x, y) {
+      Some(&self.data[self.ix(x, y)])
+    } else {
+      None
+    }
+  }
+
+  pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+    if self.in_bounds(x, y) {
+      let ix = self.ix(x, y);
+      Some(&mut self.data[ix])
+    } else {
+      None
+    }
+  }
+
+  /// # Safety
+  /// `(x, y)` must be in bounds.
+  pub unsafe fn get_unchecked(&self, x: usize, y: usize) -> &T {
+    self.data.get_unchecked(self.ix(x, y))
+  }
+
+  /// # Safety
+  /// `(x, y)` must be in bounds.
+  pub unsafe fn get_unchecked_mut(&mut self, x: usize, y: usize) -> &mut T {
+    let ix = self.ix(x, y);
+    self.data.get_unchecked_mut(ix)
+  }
+
+  pub fn row(&self, y: usize) -> &[T] { &self.data[y * self.width..(y + 1) * self.width] }
+  pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+    &mut self.data[y * self.width..(y + 1) * self.width]
+  }
+  pub fn rows(&self) -> ChunksExact<'_, T> { self.data.chunks_exact(self.width.max(1)) }
+
+  pub fn column(&self, x: usize) -> StepBy<Iter<'_, T>> {
+    assert!(x < self.width, "column {} out of bounds", x);
+    self.data[x..].iter().step_by(self.width)
+  }
+  pub fn columns(&self) -> impl Iterator<Item = StepBy<Iter<'_, T>>> {
+    (0..self.width).map(|x| self.column(x))
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &T> { self.data.iter() }
+  pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> { self.data.iter_mut() }
+
+  /// All positions in row-major order.
+  pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+    let width = self.width;
+    (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+  }
+
+  pub fn enumerate(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+    self.positions().zip(self.data.iter())
+  }
+
+  pub fn position(&self, pred: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+    let ix = self.data.iter().position(pred)?;
+    Some((ix % self.width, ix / self.width))
+  }
+
+  fn offset(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+    let x = x.checked_add_signed(dx)?;
+    let y = y.checked_add_signed(dy)?;
+    self.in_bounds(x, y).then_some((x, y))
+  }
+
+  /// In bounds orthogonal neighbors.
+  pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    OFFSETS4.into_iter().filter_map(move |d| self.offset((x, y), d))
+  }
+
+  /// In bounds orthogonal and diagonal neighbors.
+  pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    OFFSETS8.into_iter().filter_map(move |d| self.offset((x, y), d))
+  }
+
+  pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+    Grid { data: self.data.iter().map(f).collect_vec(), width: self.width, height: self.height }
+  }
+
+  pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+    self.rows().map(|row| row.iter().map(&mut f).collect::<String>()).join("\n")
+  }
+}
+
+impl<T: Clone> Grid<T> {
+  pub fn new(width: usize, height: usize, value: T) -> Self {
+    Grid { data: vec![value; width * height], width, height }
+  }
+
+  pub fn fill(&mut self, value: T) { self.data.fill(value); }
+
+  pub fn transposed(&self) -> Self {
+    Grid::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+  }
+}
+
+impl Grid<u8> {
+  /// Parses newline separated rows, which must all have the same length.
+  pub fn from_bytes(bytes: &[u8]) -> AocResult<Self> {
+    let lines = bytes.lines().collect_vec();
+    let width = lines.first().map_or(0, |l| l.len());
+    if let Some((y, _)) = lines.iter().find_position(|l| l.len() != width) {
+      return aoc_error(&format!("Grid row {} has length {}, expected {}", y, lines[y].len(), width));
+    }
+    Ok(Grid { data: lines.concat(), width, height: lines.len() })
+  }
+
+  /// Parses newline separated rows, padding short rows with `pad`.
+  pub fn from_bytes_padded(bytes: &[u8], pad: u8) -> Self {
+    let lines = bytes.lines().collect_vec();
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+    Grid::from_fn(width, lines.len(), |x, y| *lines[y].get(x).unwrap_or(&pad))
+  }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+    assert!(self.in_bounds(x, y), "({}, {}) out of bounds", x, y);
+    &self.data[self.ix(x, y)]
+  }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+  fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+    assert!(self.in_bounds(x, y), "({}, {}) out of bounds", x, y);
+    let ix = self.ix(x, y);
+    &mut self.data[ix]
+  }
+}
+
+impl<T> Index<Point2<usize>> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, p: Point2<usize>) -> &Self::Output { &self[(p.x, p.y)] }
+}
+
+impl<T> IndexMut<Point2<usize>> for Grid<T> {
+  fn index_mut(&mut self, p: Point2<usize>) -> &mut Self::Output { &mut self[(p.x, p.y)] }
+}
+
+impl Display for Grid<u8> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.render(|&b| b as char))
+  }
+}
+
+impl Display for Grid<bool> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.render(|&b| if b { '#' } else { '.' }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sorted(it: impl Iterator<Item = (usize, usize)>) -> Vec<(usize, usize)> {
+    it.sorted().collect()
+  }
+
+  #[test]
+  fn indexing() {
+    let mut g = Grid::from_fn(3, 2, |x, y| 10 * y + x);
+    assert_eq!((g.width(), g.height()), (3, 2));
+    assert_eq!((g[(2, 0)], g[(0, 1)], g[Point2::new(2, 1)]), (2, 10, 12));
+    g[(1, 1)] = 99;
+    assert_eq!(g.row(1), &[10, 99, 12]);
+    assert_eq!(g.column(1).copied().collect_vec(), vec![1, 99]);
+    assert_eq!(g.rows().count(), 2);
+    assert_eq!(g.position(|&v| v == 99), Some((1, 1)));
+    assert_eq!(g.enumerate().nth(4), Some(((1, 1), &99)));
+    assert_eq!(g.transposed()[(1, 2)], 12);
+  }
+
+  #[test]
+  fn bounds() {
+    let mut g = Grid::new(3, 2, 0u8);
+    assert!(g.in_bounds(2, 1));
+    assert!(!g.in_bounds(3, 0) && !g.in_bounds(0, 2));
+    assert_eq!(g.get(3, 0), None);
+    assert_eq!(g.get(0, 2), None);
+    // Negative coordinates cast from signed ones.
+    assert_eq!(g.get(-1isize as usize, 0), None);
+    assert!(g.get_mut(2, 1).is_some() && g.get_mut(0, usize::MAX).is_none());
+    assert!(Grid::from_vec(2, 2, vec![0; 3]).is_err());
+  }
+
+  #[test]
+  #[should_panic(expected = "out of bounds")]
+  fn index_out_of_bounds() {
+    // (3, 0) would land on (0, 1) without the check.
+    let g = Grid::new(3, 2, 0u8);
+    let _ = g[(3, 0)];
+  }
+
+  #[test]
+  fn neighbors() {
+    let g = Grid::new(3, 3, ());
+    assert_eq!(sorted(g.neighbors4(0, 0)), vec![(0, 1), (1, 0)]);
+    assert_eq!(sorted(g.neighbors4(2, 2)), vec![(1, 2), (2, 1)]);
+    assert_eq!(sorted(g.neighbors4(1, 0)), vec![(0, 0), (1, 1), (2, 0)]);
+    assert_eq!(g.neighbors4(1, 1).count(), 4);
+    assert_eq!(sorted(g.neighbors8(0, 0)), vec![(0, 1), (1, 0), (1, 1)]);
+    assert_eq!(sorted(g.neighbors8(2, 0)), vec![(1, 0), (1, 1), (2, 1)]);
+    assert_eq!(g.neighbors8(0, 1).count(), 5);
+    assert_eq!(g.neighbors8(1, 1).count(), 8);
+    let single = Grid::new(1, 1, ());
+    assert_eq!(single.neighbors8(0, 0).count(), 0);
+  }
+
+  #[test]
+  fn parsing() {
+    let g = Grid::from_bytes(b"ab\ncd\r\nef").unwrap();
+    assert_eq!((g.width(), g.height()), (2, 3));
+    assert_eq!(g.to_string(), "ab\ncd\nef");
+    assert!(Grid::from_bytes(b"ab\nc\nef").is_err());
+    let empty = Grid::from_bytes(b"").unwrap();
+    assert_eq!((empty.width(), empty.height()), (0, 0));
+
+    let g = Grid::from_bytes_padded(b"a\nbcd\n", b' ');
+    assert_eq!((g.width(), g.height()), (3, 2));
+    assert_eq!(g.row(0), b"a  ");
+    assert_eq!(g.map(|&c| c == b'c').to_string(), "...\n.#.");
+  }
+}
@@ -0,0 +1,212 @@
+use std::ops::Range;
+
+use num::PrimInt;
+
+// Set of integers stored as sorted, disjoint, non-touching half-open ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+  ranges: Vec<Range<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+  pub fn new() -> Self { IntervalSet { ranges: vec![] } }
+
+  pub fn ranges(&self) -> &[Range<T>] { &self.ranges }
+
+  pub fn is_empty(&self) -> bool { self.ranges.is_empty() }
+
+  /// Number of integers in the set.
+  pub fn len(&self) -> T {
+    self.ranges.iter().fold(T::zero(), |acc, r| acc + (r.end - r.start))
+  }
+
+  pub fn contains(&self, x: T) -> bool {
+    let i = self.ranges.partition_point(|r| r.end <= x);
+    self.ranges.get(i).is_some_and(|r| r.start <= x)
+  }
+
+  pub fn insert(&mut self, range: Range<T>) {
+    if range.is_empty() {
+      return;
+    }
+    // Everything touching `range` gets merged into it.
+    let i = self.ranges.partition_point(|r| r.end < range.start);
+    let j = self.ranges.partition_point(|r| r.start <= range.end);
+    let mut merged = range;
+    if i < j {
+      merged.start = merged.start.min(self.ranges[i].start);
+      merged.end = merged.end.max(self.ranges[j - 1].end);
+    }
+    self.ranges.splice(i..j, [merged]);
+  }
+
+  pub fn remove(&mut self, range: Range<T>) {
+    if range.is_empty() {
+      return;
+    }
+    let i = self.ranges.partition_point(|r| r.end <= range.start);
+    let j = self.ranges.partition_point(|r| r.start < range.end);
+    if i == j {
+      return;
+    }
+    let left = self.ranges[i].start..range.start;
+    let right = range.end..self.ranges[j - 1].end;
+    self.ranges.splice(i..j, [left, right].into_iter().filter(|r| !r.is_empty()));
+  }
+
+  pub fn union(&self, other: &Self) -> Self {
+    let mut res = self.clone();
+    for r in &other.ranges {
+      res.insert(r.clone());
+    }
+    res
+  }
+
+  pub fn intersection(&self, other: &Self) -> Self {
+    let mut ranges = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < self.ranges.len() && j < other.ranges.len() {
+      let (a, b) = (&self.ranges[i], &other.ranges[j]);
+      let r = a.start.max(b.start)..a.end.min(b.end);
+      if !r.is_empty() {
+        ranges.push(r);
+      }
+      if a.end < b.end {
+        i += 1;
+      } else {
+        j += 1;
+      }
+    }
+    IntervalSet { ranges }
+  }
+
+  pub fn difference(&self, other: &Self) -> Self {
+    let mut res = self.clone();
+    for r in &other.ranges {
+      res.remove(r.clone());
+    }
+    res
+  }
+
+  /// Uncovered ranges within `bounds`.
+  pub fn gaps(&self, bounds: Range<T>) -> Vec<Range<T>> {
+    let mut res = vec![];
+    let mut pos = bounds.start;
+    for r in &self.ranges {
+      if r.start >= bounds.end {
+        break;
+      }
+      if pos < r.start {
+        res.push(pos..r.start);
+      }
+      pos = pos.max(r.end);
+    }
+    if pos < bounds.end {
+      res.push(pos..bounds.end);
+    }
+    res
+  }
+}
+
+impl<T: PrimInt> FromIterator<Range<T>> for IntervalSet<T> {
+  fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+    let mut res = IntervalSet::new();
+    for r in iter {
+      res.insert(r);
+    }
+    res
+  }
+}
+
+#[cfg(test)]
+// Expected ranges are compared as arrays, even when there is only one.
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+  use super::*;
+
+  fn set(ranges: &[Range<i32>]) -> IntervalSet<i32> { ranges.iter().cloned().collect() }
+
+  #[test]
+  fn empty() {
+    let s = set(&[]);
+    assert!(s.is_empty());
+    assert_eq!((s.len(), s.contains(0)), (0, false));
+    assert_eq!(s.gaps(0..5), [0..5]);
+    // Empty ranges are ignored.
+    assert!(set(&[3..3, Range { start: 5, end: 2 }]).is_empty());
+    let mut s = set(&[1..4]);
+    s.remove(2..2);
+    assert_eq!(s.ranges(), [1..4]);
+  }
+
+  #[test]
+  fn insert_merges() {
+    // Overlapping, touching and nested ranges become one.
+    assert_eq!(set(&[1..4, 3..6]).ranges(), [1..6]);
+    assert_eq!(set(&[1..4, 4..6]).ranges(), [1..6]);
+    assert_eq!(set(&[4..6, 1..4]).ranges(), [1..6]);
+    assert_eq!(set(&[1..10, 3..4]).ranges(), [1..10]);
+    assert_eq!(set(&[1..2, 5..6, 8..9, 0..8]).ranges(), [0..9]);
+    // With a gap of one they stay apart.
+    let s = set(&[-3..1, 2..4, 10..12]);
+    assert_eq!(s.ranges(), [-3..1, 2..4, 10..12]);
+    assert_eq!(s.len(), 8);
+    assert!(s.contains(-3) && s.contains(0) && !s.contains(1) && s.contains(3));
+    assert!(!s.contains(4) && !s.contains(12) && !s.contains(-4));
+  }
+
+  #[test]
+  fn remove() {
+    let mut s = set(&[0..10, 20..30]);
+    s.remove(3..5);
+    assert_eq!(s.ranges(), [0..3, 5..10, 20..30]);
+    s.remove(8..25);
+    assert_eq!(s.ranges(), [0..3, 5..8, 25..30]);
+    s.remove(10..20);
+    assert_eq!(s.ranges(), [0..3, 5..8, 25..30]);
+    s.remove(-5..3);
+    assert_eq!(s.ranges(), [5..8, 25..30]);
+    s.remove(0..100);
+    assert!(s.is_empty());
+  }
+
+  #[test]
+  fn set_operations() {
+    let a = set(&[0..5, 10..15]);
+    let b = set(&[3..12, 14..20]);
+    assert_eq!(a.union(&b).ranges(), [0..20]);
+    assert_eq!(a.intersection(&b).ranges(), [3..5, 10..12, 14..15]);
+    assert_eq!(a.difference(&b).ranges(), [0..3, 12..14]);
+    assert_eq!(b.difference(&a).ranges(), [5..10, 15..20]);
+    assert_eq!(a.gaps(-2..12), [-2..0, 5..10]);
+    assert_eq!(a.gaps(6..9), [6..9]);
+    assert!(a.gaps(1..4).is_empty());
+  }
+
+  #[test]
+  fn matches_bitmap() {
+    // Random edits checked against a plain array of flags.
+    let mut x = 7u32;
+    let mut rand = |n: u32| {
+      x = x.wrapping_mul(1103515245).wrapping_add(12345);
+      (x >> 16) % n
+    };
+    let mut s = IntervalSet::new();
+    let mut flags = [false; 64];
+    for _ in 0..1000 {
+      let (a, b) = (rand(64) as usize, rand(64) as usize);
+      let range = a.min(b)..a.max(b);
+      let insert = rand(2) == 0;
+      if insert {
+        s.insert(range.clone());
+      } else {
+        s.remove(range.clone());
+      }
+      flags[range].fill(insert);
+
+      assert!((0..64).all(|i| s.contains(i) == flags[i]));
+      assert_eq!(s.len(), flags.iter().filter(|&&f| f).count());
+      assert!(s.ranges().windows(2).all(|w| w[0].end < w[1].start));
+    }
+  }
+}
@@ -0,0 +1,396 @@
+// Format-string style line parsing.
+//
+//   let p = Pattern::new("Valve {name} has flow rate={i32}; {_} to {_} {list<, >}")?;
+//   let (name, rate, dsts): (String, i32, Vec<String>) = p.parse(line)?;
+//
+// Placeholders:
+//   {i8} .. {i64}, {u8} .. {u64}, {isize}, {usize}  an integer in that type's range
+//   {list<SEP>}                                      items separated by SEP
+//   {_}                                              anything, not captured
+//   {anything else}                                  a string
+//
+// Everything but integers extends up to the next literal part of the pattern
+// (or the end of the line), so those need a literal after them.
+
+use std::fmt;
+
+use bstr::ByteSlice;
+
+use error::{AocError, AocResult};
+
+#[derive(Debug)]
+enum Segment {
+  Literal(Vec<u8>),
+  // Type name and range.
+  Int(&'static str, i128, i128),
+  Str,
+  Skip,
+  List(Vec<u8>),
+}
+
+#[derive(Debug)]
+pub enum Field {
+  Int(i128),
+  Str(String),
+  List(Vec<String>),
+}
+
+macro_rules! int_types {
+  ($($t: ty),+) => {
+    &[$((stringify!($t), <$t>::MIN as i128, <$t>::MAX as i128)),+]
+  };
+}
+
+const INT_TYPES: &[(&str, i128, i128)] =
+  int_types!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+#[derive(Debug)]
+pub struct ParseError {
+  col: usize,
+  msg: String,
+}
+
+impl ParseError {
+  fn new(col: usize, msg: String) -> Self { ParseError { col, msg } }
+
+  fn at_line(self, line: usize) -> AocError {
+    AocError::ParserError(format!("line {}, column {}: {}", line, self.col + 1, self.msg))
+  }
+
+  fn into_aoc(self) -> AocError { AocError::ParserError(self.to_string()) }
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "column {}: {}", self.col + 1, self.msg)
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+pub struct Pattern {
+  segments: Vec<Segment>,
+}
+
+impl Pattern {
+  pub fn new(pattern: &str) -> AocResult<Self> {
+    let mut segments = vec![];
+    let mut rest = pattern;
+    while !rest.is_empty() {
+      let Some(open) = rest.find('{') else {
+        segments.push(Segment::Literal(rest.as_bytes().to_vec()));
+        break;
+      };
+      if open > 0 {
+        segments.push(Segment::Literal(rest.as_bytes()[..open].to_vec()));
+      }
+      let Some(close) = rest[open..].find('}').map(|i| i + open) else {
+        return Err(AocError::ParserError(format!("Unclosed '{{' in pattern \"{}\"", pattern)));
+      };
+      let spec = &rest[open + 1..close];
+      let int = INT_TYPES.iter().find(|(name, ..)| *name == spec);
+      let segment = match (spec, int) {
+        (_, Some(&(name, min, max))) => Segment::Int(name, min, max),
+        ("_", _) => Segment::Skip,
+        _ => match spec.strip_prefix("list<").and_then(|s| s.strip_suffix('>')) {
+          Some("") => {
+            return Err(AocError::ParserError(format!("Empty list separator in \"{}\"", pattern)))
+          }
+          Some(sep) => Segment::List(sep.as_bytes().to_vec()),
+          None => Segment::Str,
+        },
+      };
+
+      if let Some(prev) = segments.last() {
+        if !matches!(prev, Segment::Literal(_) | Segment::Int(..)) {
+          return Err(AocError::ParserError(format!(
+            "Placeholder {{{}}} directly follows another one in \"{}\"",
+            spec, pattern
+          )));
+        }
+      }
+      segments.push(segment);
+      rest = &rest[close + 1..];
+    }
+    Ok(Pattern { segments })
+  }
+
+  /// Matches a line, returning the captured fields with their columns.
+  pub fn fields(&self, line: &[u8]) -> Result<Vec<(usize, Field)>, ParseError> {
+    let mut res = vec![];
+    let mut pos = 0;
+    for (i, segment) in self.segments.iter().enumerate() {
+      // Text fields run until the next literal.
+      let until_next = |pos: usize| -> Result<usize, ParseError> {
+        match self.segments.get(i + 1) {
+          Some(Segment::Literal(lit)) => line[pos..].find(lit).map(|j| pos + j).ok_or_else(|| {
+            ParseError::new(pos, format!("expected \"{}\"", lit.to_str_lossy()))
+          }),
+          _ => Ok(line.len()),
+        }
+      };
+
+      match segment {
+        Segment::Literal(lit) => {
+          if !line[pos..].starts_with(lit) {
+            let found = &line[pos..(pos + lit.len()).min(line.len())];
+            return Err(ParseError::new(
+              pos,
+              format!(
+                "expected \"{}\", found \"{}\"",
+                lit.to_str_lossy(),
+                found.to_str_lossy()
+              ),
+            ));
+          }
+          pos += lit.len();
+        }
+        &Segment::Int(name, min, max) => {
+          let start = pos;
+          let negative = line.get(pos) == Some(&b'-');
+          if negative {
+            pos += 1;
+          }
+          let digits_start = pos;
+          let mut n = 0i128;
+          let mut too_large = false;
+          while let Some(c) = line.get(pos).filter(|c| c.is_ascii_digit()) {
+            // Keep consuming digits so the error shows the whole number.
+            match n.checked_mul(10).and_then(|n| n.checked_add((c - b'0') as i128)) {
+              Some(m) => n = m,
+              None => too_large = true,
+            }
+            pos += 1;
+          }
+          if pos == digits_start {
+            return Err(ParseError::new(start, "expected integer".to_string()));
+          }
+          let n = if negative { -n } else { n };
+          if too_large || n < min || n > max {
+            let text = line[start..pos].to_str_lossy();
+            return Err(ParseError::new(start, format!("{} does not fit in {}", text, name)));
+          }
+          res.push((start, Field::Int(n)));
+        }
+        Segment::Str => {
+          let end = until_next(pos)?;
+          res.push((pos, Field::Str(line[pos..end].to_str_lossy().into_owned())));
+          pos = end;
+        }
+        Segment::Skip => {
+          pos = until_next(pos)?;
+        }
+        Segment::List(sep) => {
+          let end = until_next(pos)?;
+          let items = line[pos..end]
+            .split_str(sep)
+            .map(|item| item.trim().to_str_lossy().into_owned())
+            .collect();
+          res.push((pos, Field::List(items)));
+          pos = end;
+        }
+      }
+    }
+    if pos != line.len() {
+      return Err(ParseError::new(
+        pos,
+        format!("unexpected trailing \"{}\"", line[pos..].to_str_lossy()),
+      ));
+    }
+    Ok(res)
+  }
+
+  pub fn parse<T: FromFields>(&self, line: &[u8]) -> AocResult<T> {
+    self
+      .fields(line)
+      .and_then(T::from_fields)
+      .map_err(ParseError::into_aoc)
+  }
+
+  /// Parses every non-empty line, reporting errors with 1-based line numbers.
+  pub fn parse_lines<T: FromFields>(&self, bytes: &[u8]) -> AocResult<Vec<T>> {
+    bytes
+      .lines()
+      .enumerate()
+      .filter(|(_, line)| !line.is_empty())
+      .map(|(i, line)| {
+        self
+          .fields(line)
+          .and_then(T::from_fields)
+          .map_err(|e| e.at_line(i + 1))
+      })
+      .collect()
+  }
+}
+
+pub trait FromField: Sized {
+  fn from_field(field: Field) -> Result<Self, String>;
+}
+
+macro_rules! int_from_field {
+  ($($t: ty),+) => {$(
+    impl FromField for $t {
+      fn from_field(field: Field) -> Result<Self, String> {
+        match field {
+          Field::Int(n) => n
+            .try_into()
+            .map_err(|_| format!("{} does not fit in {}", n, stringify!($t))),
+          Field::Str(s) => s
+            .parse()
+            .map_err(|_| format!("\"{}\" is not a valid {}", s, stringify!($t))),
+          Field::List(_) => Err(format!("expected {}, got a list", stringify!($t))),
+        }
+      }
+    }
+
+    impl FromField for Vec<$t> {
+      fn from_field(field: Field) -> Result<Self, String> {
+        match field {
+          Field::List(items) => items.into_iter().map(|s| <$t>::from_field(Field::Str(s))).collect(),
+          _ => Err(format!("expected list of {}", stringify!($t))),
+        }
+      }
+    }
+  )+};
+}
+
+int_from_field!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl FromField for String {
+  fn from_field(field: Field) -> Result<Self, String> {
+    match field {
+      Field::Int(n) => Ok(n.to_string()),
+      Field::Str(s) => Ok(s),
+      Field::List(_) => Err("expected string, got a list".to_string()),
+    }
+  }
+}
+
+impl FromField for Vec<String> {
+  fn from_field(field: Field) -> Result<Self, String> {
+    match field {
+      Field::List(items) => Ok(items),
+      _ => Err("expected list".to_string()),
+    }
+  }
+}
+
+pub trait FromFields: Sized {
+  fn from_fields(fields: Vec<(usize, Field)>) -> Result<Self, ParseError>;
+}
+
+macro_rules! tuple_from_fields {
+  ($n: literal; $($t: ident),+) => {
+    impl<$($t: FromField),+> FromFields for ($($t,)+) {
+      fn from_fields(fields: Vec<(usize, Field)>) -> Result<Self, ParseError> {
+        if fields.len() != $n {
+          return Err(ParseError::new(
+            0,
+            format!("pattern captures {} fields, expected {}", fields.len(), $n),
+          ));
+        }
+        let mut it = fields.into_iter();
+        Ok(($({
+          let (col, field) = it.next().unwrap();
+          $t::from_field(field).map_err(|msg| ParseError::new(col, msg))?
+        },)+))
+      }
+    }
+  };
+}
+
+tuple_from_fields!(1; A);
+tuple_from_fields!(2; A, B);
+tuple_from_fields!(3; A, B, C);
+tuple_from_fields!(4; A, B, C, D);
+tuple_from_fields!(5; A, B, C, D, E);
+tuple_from_fields!(6; A, B, C, D, E, F);
+tuple_from_fields!(7; A, B, C, D, E, F, G);
+tuple_from_fields!(8; A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn err<T: std::fmt::Debug>(res: AocResult<T>) -> String {
+    match res.unwrap_err() {
+      AocError::ParserError(msg) => msg,
+      e => panic!("unexpected error {:?}", e),
+    }
+  }
+
+  #[test]
+  fn fields() {
+    let p = Pattern::new("Valve {name} has flow rate={i32}; {_} to {_} {list<, >}").unwrap();
+    let line = b"Valve AA has flow rate=-3; tunnels lead to valves DD, II, BB";
+    let (name, rate, dsts): (String, i32, Vec<String>) = p.parse(line).unwrap();
+    assert_eq!((name.as_str(), rate), ("AA", -3));
+    assert_eq!(dsts, ["DD", "II", "BB"]);
+
+    let p = Pattern::new("{u8},{i64}:{list<;>}").unwrap();
+    let (a, b, c): (u8, i64, Vec<u16>) = p.parse(b"255,-9223372036854775808:1;2;3").unwrap();
+    assert_eq!((a, b, c), (255, i64::MIN, vec![1, 2, 3]));
+  }
+
+  #[test]
+  fn int_ranges() {
+    let p = Pattern::new("{u8} {i8}").unwrap();
+    assert_eq!(p.parse::<(i32, i32)>(b"0 -128").unwrap(), (0, -128));
+    assert_eq!(err(p.parse::<(i32, i32)>(b"256 0")), "column 1: 256 does not fit in u8");
+    assert_eq!(err(p.parse::<(i32, i32)>(b"-1 0")), "column 1: -1 does not fit in u8");
+    assert_eq!(err(p.parse::<(i32, i32)>(b"1 128")), "column 3: 128 does not fit in i8");
+    let p = Pattern::new("{u64}").unwrap();
+    assert_eq!(p.parse::<(u64,)>(b"18446744073709551615").unwrap(), (u64::MAX,));
+    assert!(p.parse::<(u64,)>(b"99999999999999999999999999999999999999999").is_err());
+    // The declared range is checked first, the target type after.
+    let p = Pattern::new("{i32}").unwrap();
+    assert_eq!(err(p.parse::<(u8,)>(b"300")), "column 1: 300 does not fit in u8");
+  }
+
+  #[test]
+  fn mismatches() {
+    let p = Pattern::new("move {usize} from {usize}").unwrap();
+    assert_eq!(err(p.parse::<(usize, usize)>(b"move x from 1")), "column 6: expected integer");
+    assert_eq!(
+      err(p.parse::<(usize, usize)>(b"move 1 to 2")),
+      "column 7: expected \" from \", found \" to 2\""
+    );
+    assert_eq!(
+      err(p.parse::<(usize, usize)>(b"move 1 from 2 now")),
+      "column 14: unexpected trailing \" now\""
+    );
+    assert_eq!(
+      err(p.parse::<(usize,)>(b"move 1 from 2")),
+      "column 1: pattern captures 2 fields, expected 1"
+    );
+    let p = Pattern::new("{a}: {b}").unwrap();
+    assert_eq!(err(p.parse::<(String, String)>(b"a b")), "column 1: expected \": \"");
+  }
+
+  #[test]
+  fn bad_patterns() {
+    assert!(Pattern::new("{a").is_err());
+    assert!(Pattern::new("{a}{b}").is_err());
+    assert!(Pattern::new("{_}{i32}").is_err());
+    assert!(Pattern::new("{list<>}").is_err());
+    // Integers end at the first non-digit, so anything can follow them.
+    assert!(Pattern::new("{i32}{a}").is_ok());
+  }
+
+  #[test]
+  fn lines() {
+    let p = Pattern::new("{i32}-{i32}").unwrap();
+    assert_eq!(p.parse_lines::<(i32, i32)>(b"1-2\n\n3-4\n").unwrap(), [(1, 2), (3, 4)]);
+    assert_eq!(
+      err(p.parse_lines::<(i32, i32)>(b"1-2\n\n3+4\n")),
+      "line 3, column 2: expected \"-\", found \"+\""
+    );
+  }
+
+  #[test]
+  fn display() {
+    let e = ParseError::new(4, "expected integer".to_string());
+    assert_eq!(e.to_string(), "column 5: expected integer");
+    assert!(format!("{:?}", e).contains("expected integer"));
+  }
+}
@@ -0,0 +1,280 @@
+use std::fmt::{self, Display};
+use std::ops::{Mul, Neg};
+
+use derive_more::{Add, AddAssign, Constructor, Sub, SubAssign};
+use num::{NumCast, PrimInt, Signed};
+
+#[derive(
+  Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+  Add, Sub, AddAssign, SubAssign, Constructor,
+)]
+pub struct Point2<T> {
+  pub x: T,
+  pub y: T,
+}
+
+#[derive(
+  Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+  Add, Sub, AddAssign, SubAssign, Constructor,
+)]
+pub struct Point3<T> {
+  pub x: T,
+  pub y: T,
+  pub z: T,
+}
+
+/// `|a - b|`, which has to fit in `T`: for signed types, distances between
+/// far apart values like `i32::MIN` and `i32::MAX` overflow. Cast to a wider
+/// type first when coordinates can get that large.
+fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+  if a > b { a - b } else { b - a }
+}
+
+// Distances are in `T` and share the overflow limits of `abs_diff`.
+impl<T: PrimInt> Point2<T> {
+  pub fn manhattan(self, other: Self) -> T {
+    abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+  }
+  pub fn chebyshev(self, other: Self) -> T {
+    abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+  }
+}
+
+impl<T: PrimInt> Point3<T> {
+  pub fn manhattan(self, other: Self) -> T {
+    abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+  }
+  pub fn chebyshev(self, other: Self) -> T {
+    abs_diff(self.x, other.x).max(abs_diff(self.y, other.y)).max(abs_diff(self.z, other.z))
+  }
+}
+
+impl<T: PrimInt + Signed> Point2<T> {
+  /// Component-wise signum, handy for stepping towards another point.
+  pub fn signum(self) -> Self { Point2::new(self.x.signum(), self.y.signum()) }
+
+  pub fn neighbors4(self) -> [Self; 4] { Dir::ALL.map(|d| self + d.delta()) }
+
+  /// Neighbors clockwise starting from the top left, y pointing down.
+  pub fn neighbors8(self) -> [Self; 8] {
+    let (o, l) = (T::zero(), T::one());
+    [(-l, -l), (o, -l), (l, -l), (l, o), (l, l), (o, l), (-l, l), (-l, o)]
+      .map(|(dx, dy)| self + Point2::new(dx, dy))
+  }
+}
+
+impl<T: PrimInt + Signed> Point3<T> {
+  pub fn signum(self) -> Self { Point3::new(self.x.signum(), self.y.signum(), self.z.signum()) }
+
+  pub fn neighbors6(self) -> [Self; 6] {
+    let (o, l) = (T::zero(), T::one());
+    [(l, o, o), (o, l, o), (o, o, l), (-l, o, o), (o, -l, o), (o, o, -l)]
+      .map(|(dx, dy, dz)| self + Point3::new(dx, dy, dz))
+  }
+}
+
+impl<T: NumCast + Copy> Point2<T> {
+  /// Converts to another number type, `None` if a coordinate doesn't fit.
+  pub fn try_cast<U: NumCast>(self) -> Option<Point2<U>> {
+    Some(Point2::new(U::from(self.x)?, U::from(self.y)?))
+  }
+}
+
+impl<T: NumCast + Copy> Point3<T> {
+  pub fn try_cast<U: NumCast>(self) -> Option<Point3<U>> {
+    Some(Point3::new(U::from(self.x)?, U::from(self.y)?, U::from(self.z)?))
+  }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+  type Output = Self;
+
+  fn mul(self, k: T) -> Self::Output { Point2::new(self.x * k, self.y * k) }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+  type Output = Self;
+
+  fn mul(self, k: T) -> Self::Output { Point3::new(self.x * k, self.y * k, self.z * k) }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+  type Output = Self;
+
+  fn neg(self) -> Self::Output { Point2::new(-self.x, -self.y) }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+  type Output = Self;
+
+  fn neg(self) -> Self::Output { Point3::new(-self.x, -self.y, -self.z) }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+  fn from((x, y): (T, T)) -> Self { Point2 { x, y } }
+}
+
+impl<T> From<[T; 2]> for Point2<T> {
+  fn from([x, y]: [T; 2]) -> Self { Point2 { x, y } }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+  fn from(p: Point2<T>) -> Self { (p.x, p.y) }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+  fn from((x, y, z): (T, T, T)) -> Self { Point3 { x, y, z } }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+  fn from([x, y, z]: [T; 3]) -> Self { Point3 { x, y, z } }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+  fn from(p: Point3<T>) -> Self { (p.x, p.y, p.z) }
+}
+
+impl<T: Display> Display for Point2<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "({}, {})", self.x, self.y) }
+}
+
+impl<T: Display> Display for Point3<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "({}, {}, {})", self.x, self.y, self.z)
+  }
+}
+
+// Screen directions, y pointing down. Discriminants count clockwise turns
+// from facing right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir {
+  Right = 0,
+  Down = 1,
+  Left = 2,
+  Up = 3,
+}
+
+impl Dir {
+  pub const ALL: [Dir; 4] = [Dir::Right, Dir::Down, Dir::Left, Dir::Up];
+
+  pub fn from_value(n: i32) -> Dir { Dir::ALL[n.rem_euclid(4) as usize] }
+  pub fn value(self) -> i32 { self as i32 }
+
+  /// Turns clockwise `n` quarter turns, negative `n` turns counter clockwise.
+  pub fn rot(self, n: i32) -> Dir { Dir::from_value(self.value() + n) }
+  pub fn right(self) -> Dir { self.rot(1) }
+  pub fn left(self) -> Dir { self.rot(-1) }
+  pub fn opposite(self) -> Dir { self.rot(2) }
+
+  pub fn delta<T: PrimInt + Signed>(self) -> Point2<T> {
+    let (o, l) = (T::zero(), T::one());
+    match self {
+      Dir::Right => Point2::new(l, o),
+      Dir::Down => Point2::new(o, l),
+      Dir::Left => Point2::new(-l, o),
+      Dir::Up => Point2::new(o, -l),
+    }
+  }
+
+  /// Parses U/D/L/R, N/S/W/E and arrows.
+  pub fn from_byte(b: u8) -> Option<Dir> {
+    match b {
+      b'R' | b'E' | b'>' => Some(Dir::Right),
+      b'D' | b'S' | b'v' => Some(Dir::Down),
+      b'L' | b'W' | b'<' => Some(Dir::Left),
+      b'U' | b'N' | b'^' => Some(Dir::Up),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  type P = Point2<i32>;
+
+  #[test]
+  fn dirs() {
+    assert_eq!(Dir::Right.right(), Dir::Down);
+    assert_eq!(Dir::Right.left(), Dir::Up);
+    assert_eq!(Dir::Up.right(), Dir::Right);
+    assert_eq!(Dir::Left.rot(-3), Dir::Up);
+    assert_eq!(Dir::Down.rot(4 * 1000 + 1), Dir::Left);
+    for d in Dir::ALL {
+      assert_eq!(d.opposite().opposite(), d);
+      assert_eq!(d.opposite().delta::<i32>(), -d.delta::<i32>());
+      assert_eq!(d.left().right(), d);
+      assert_eq!(Dir::from_value(d.value()), d);
+    }
+    assert_eq!(Dir::from_value(-1), Dir::Up);
+    assert_eq!(Dir::Down.delta::<i64>(), Point2::new(0, 1));
+
+    let parsed = |s: &[u8]| s.iter().map(|&b| Dir::from_byte(b)).collect::<Option<Vec<_>>>();
+    let expected = Some(Dir::ALL.to_vec());
+    for s in [&b"RDLU"[..], b"ESWN", b">v<^"] {
+      assert_eq!(parsed(s), expected);
+    }
+    assert_eq!(Dir::from_byte(b'r'), None);
+    assert_eq!(Dir::from_byte(b'V'), None);
+  }
+
+  #[test]
+  fn distances() {
+    let (a, b) = (P::new(1, -2), P::new(-3, 5));
+    assert_eq!((a.manhattan(b), b.manhattan(a), a.chebyshev(b)), (11, 11, 7));
+    assert_eq!((a.manhattan(a), a.chebyshev(a)), (0, 0));
+    let (a, b) = (Point3::new(0u8, 10, 3), Point3::new(5, 2, 3));
+    assert_eq!((a.manhattan(b), a.chebyshev(b)), (13, 8));
+    // Unsigned coordinates work in either order.
+    assert_eq!(Point2::new(0u32, 7).manhattan(Point2::new(4, 0)), 11);
+    // The largest distance that still fits.
+    assert_eq!(P::new(i32::MAX, 0).manhattan(P::new(0, 0)), i32::MAX);
+    assert_eq!(P::new(-1, 0).chebyshev(P::new(i32::MAX - 1, 0)), i32::MAX);
+  }
+
+  #[test]
+  fn neighbors() {
+    let p = P::new(3, 4);
+    assert_eq!(p.neighbors4(), [(4, 4), (3, 5), (2, 4), (3, 3)].map(P::from));
+    let n8 = p.neighbors8();
+    assert_eq!(n8[0], P::new(2, 3));
+    assert_eq!(n8[3], P::new(4, 4));
+    assert!(n8.iter().all(|&q| q.chebyshev(p) == 1));
+    assert!(p.neighbors4().iter().all(|q| n8.contains(q)));
+    let n6 = Point3::new(0, 0, 0).neighbors6();
+    assert!(n6.iter().all(|&q| q.manhattan(Point3::default()) == 1));
+    assert!(n6.iter().all(|&q| n6.contains(&-q)));
+  }
+
+  #[test]
+  fn operators() {
+    let (a, b) = (P::new(1, 2), P::new(10, -20));
+    assert_eq!(a + b, P::new(11, -18));
+    assert_eq!(a - b, P::new(-9, 22));
+    assert_eq!(-a, P::new(-1, -2));
+    assert_eq!(a * 3, P::new(3, 6));
+    let mut c = a;
+    c += b;
+    c -= a * 2;
+    assert_eq!(c, P::new(9, -22));
+    assert_eq!((b - a).signum(), P::new(1, -1));
+    assert_eq!(Point3::new(1, 2, 3) * -1, -Point3::new(1, 2, 3));
+    assert_eq!(Point3::new(0, -5, 2).signum(), Point3::new(0, -1, 1));
+    assert_eq!(<(i32, i32)>::from(a), (1, 2));
+    assert_eq!(P::from([1, 2]), a);
+    assert_eq!(Point3::from((1, 2, 3)), Point3::from([1, 2, 3]));
+    assert_eq!(a.to_string(), "(1, 2)");
+    assert_eq!(Point3::new(1, 2, 3).to_string(), "(1, 2, 3)");
+  }
+
+  #[test]
+  fn try_cast() {
+    assert_eq!(P::new(3, -4).try_cast::<i8>(), Some(Point2::new(3i8, -4)));
+    assert_eq!(P::new(3, -4).try_cast::<u8>(), None);
+    assert_eq!(P::new(300, 4).try_cast::<u8>(), None);
+    assert_eq!(Point2::new(7usize, 8).try_cast::<i64>(), Some(Point2::new(7, 8)));
+    assert_eq!(Point3::new(1i64, 2, 1 << 40).try_cast::<i32>(), None);
+    assert_eq!(Point3::new(1i64, 2, 3).try_cast::<u16>(), Some(Point3::new(1, 2, 3)));
+  }
+}
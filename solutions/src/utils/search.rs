@@ -0,0 +1,376 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::Add;
+
+use ahash::{AHashMap, AHashSet};
+use num::Zero;
+
+// Graph searches over implicit graphs. Nodes are anything hashable, edges come
+// from a neighbor function called on demand.
+
+/// Per node storage for the searches. Hash maps work for any node; `Dense`
+/// is much faster when nodes map to small indices.
+pub trait NodeMap<N, V> {
+  fn get(&self, node: &N) -> Option<V>;
+  fn insert(&mut self, node: N, value: V);
+}
+
+impl<N: Hash + Eq, V: Clone> NodeMap<N, V> for AHashMap<N, V> {
+  fn get(&self, node: &N) -> Option<V> { AHashMap::get(self, node).cloned() }
+  fn insert(&mut self, node: N, value: V) { AHashMap::insert(self, node, value); }
+}
+
+/// Set of nodes already seen, see `NodeMap`.
+pub trait NodeSet<N> {
+  fn contains(&self, node: &N) -> bool;
+  /// Whether `node` wasn't in the set yet.
+  fn insert(&mut self, node: N) -> bool;
+}
+
+impl<N: Hash + Eq> NodeSet<N> for AHashSet<N> {
+  fn contains(&self, node: &N) -> bool { HashSet::contains(self, node) }
+  fn insert(&mut self, node: N) -> bool { HashSet::insert(self, node) }
+}
+
+/// Storage for nodes that `index` maps into `0..len`.
+pub struct Dense<T, F> {
+  data: Vec<T>,
+  index: F,
+}
+
+impl<T: Clone + Default, F> Dense<T, F> {
+  pub fn new(len: usize, index: F) -> Self { Dense { data: vec![T::default(); len], index } }
+}
+
+impl<N, V: Clone, F: Fn(&N) -> usize> NodeMap<N, V> for Dense<Option<V>, F> {
+  fn get(&self, node: &N) -> Option<V> { self.data[(self.index)(node)].clone() }
+  fn insert(&mut self, node: N, value: V) { self.data[(self.index)(&node)] = Some(value); }
+}
+
+impl<N, F: Fn(&N) -> usize> NodeSet<N> for Dense<bool, F> {
+  fn contains(&self, node: &N) -> bool { self.data[(self.index)(node)] }
+  fn insert(&mut self, node: N) -> bool {
+    !std::mem::replace(&mut self.data[(self.index)(&node)], true)
+  }
+}
+
+/// Stands in for the parent map when no paths are needed.
+pub struct NoParents;
+
+impl<N> NodeMap<N, N> for NoParents {
+  fn get(&self, _: &N) -> Option<N> { None }
+  fn insert(&mut self, _: N, _: N) {}
+}
+
+pub struct SearchResult<N, C, D = AHashMap<N, C>, P = AHashMap<N, N>> {
+  dist: D,
+  parent: P,
+  goal: Option<N>,
+  cost: PhantomData<C>,
+}
+
+impl<N, C, D: NodeMap<N, C>, P> SearchResult<N, C, D, P> {
+  fn new(dist: D, parent: P) -> Self {
+    SearchResult { dist, parent, goal: None, cost: PhantomData }
+  }
+
+  /// First goal node reached, if any.
+  pub fn goal(&self) -> Option<&N> { self.goal.as_ref() }
+  pub fn goal_dist(&self) -> Option<C> { self.goal.as_ref().and_then(|g| self.dist(g)) }
+
+  pub fn dist(&self, node: &N) -> Option<C> { self.dist.get(node) }
+  pub fn distances(&self) -> &D { &self.dist }
+}
+
+impl<N: Hash + Eq + Clone, C, D: NodeMap<N, C>> SearchResult<N, C, D> {
+  /// Path from one of the starts to `node`, both ends included.
+  pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+    self.dist.get(node)?;
+    let mut path = vec![node.clone()];
+    while let Some(p) = self.parent.get(path.last().unwrap()) {
+      path.push(p.clone());
+    }
+    path.reverse();
+    Some(path)
+  }
+
+  pub fn goal_path(&self) -> Option<Vec<N>> { self.goal.as_ref().and_then(|g| self.path_to(g)) }
+}
+
+/// Unweighted shortest paths from `starts`, stopping at the first goal found.
+/// Pass `|_| false` as goal to explore everything reachable.
+pub fn bfs<N, I>(
+  starts: impl IntoIterator<Item = N>,
+  neighbors: impl FnMut(&N) -> I,
+  is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, usize>
+where
+  N: Hash + Eq + Clone,
+  I: IntoIterator<Item = N>,
+{
+  bfs_in(AHashMap::new(), AHashMap::new(), starts, neighbors, is_goal)
+}
+
+/// `bfs` keeping distances in `dist` and parents in `parent`, which can be
+/// `NoParents` when no path is needed.
+pub fn bfs_in<N, D, P, I>(
+  dist: D,
+  parent: P,
+  starts: impl IntoIterator<Item = N>,
+  mut neighbors: impl FnMut(&N) -> I,
+  mut is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, usize, D, P>
+where
+  N: Clone,
+  D: NodeMap<N, usize>,
+  P: NodeMap<N, N>,
+  I: IntoIterator<Item = N>,
+{
+  let mut res = SearchResult::new(dist, parent);
+  let mut queue = VecDeque::new();
+  for start in starts {
+    if res.dist.get(&start).is_none() {
+      res.dist.insert(start.clone(), 0);
+      queue.push_back(start);
+    }
+  }
+
+  while let Some(cur) = queue.pop_front() {
+    if is_goal(&cur) {
+      res.goal = Some(cur);
+      break;
+    }
+    let d = res.dist.get(&cur).unwrap();
+    for next in neighbors(&cur) {
+      if res.dist.get(&next).is_none() {
+        res.dist.insert(next.clone(), d + 1);
+        res.parent.insert(next.clone(), cur.clone());
+        queue.push_back(next);
+      }
+    }
+  }
+  res
+}
+
+/// Weighted shortest paths with non-negative costs. `neighbors` yields
+/// `(node, edge cost)` pairs.
+pub fn dijkstra<N, C, I>(
+  starts: impl IntoIterator<Item = N>,
+  neighbors: impl FnMut(&N) -> I,
+  is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, C>
+where
+  N: Hash + Eq + Clone + Ord,
+  C: Zero + Ord + Copy + Add<Output = C>,
+  I: IntoIterator<Item = (N, C)>,
+{
+  astar(starts, neighbors, |_| C::zero(), is_goal)
+}
+
+/// Dijkstra guided by `heuristic`, which must never overestimate the
+/// remaining cost to a goal.
+pub fn astar<N, C, I>(
+  starts: impl IntoIterator<Item = N>,
+  neighbors: impl FnMut(&N) -> I,
+  heuristic: impl FnMut(&N) -> C,
+  is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, C>
+where
+  N: Hash + Eq + Clone + Ord,
+  C: Zero + Ord + Copy + Add<Output = C>,
+  I: IntoIterator<Item = (N, C)>,
+{
+  astar_in(AHashMap::new(), AHashMap::new(), starts, neighbors, heuristic, is_goal)
+}
+
+/// `astar` with its own storage, see `bfs_in`.
+pub fn astar_in<N, C, D, P, I>(
+  dist: D,
+  parent: P,
+  starts: impl IntoIterator<Item = N>,
+  mut neighbors: impl FnMut(&N) -> I,
+  mut heuristic: impl FnMut(&N) -> C,
+  mut is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, C, D, P>
+where
+  N: Clone + Ord,
+  C: Zero + Ord + Copy + Add<Output = C>,
+  D: NodeMap<N, C>,
+  P: NodeMap<N, N>,
+  I: IntoIterator<Item = (N, C)>,
+{
+  let mut res = SearchResult::new(dist, parent);
+  let mut heap = BinaryHeap::new();
+  for start in starts {
+    res.dist.insert(start.clone(), C::zero());
+    heap.push(Reverse((heuristic(&start), C::zero(), start)));
+  }
+
+  while let Some(Reverse((_, d, cur))) = heap.pop() {
+    if res.dist.get(&cur).is_some_and(|best| best < d) {
+      continue; // Stale entry.
+    }
+    if is_goal(&cur) {
+      res.goal = Some(cur);
+      break;
+    }
+    for (next, cost) in neighbors(&cur) {
+      let nd = d + cost;
+      if res.dist.get(&next).is_none_or(|old| nd < old) {
+        res.dist.insert(next.clone(), nd);
+        res.parent.insert(next.clone(), cur.clone());
+        heap.push(Reverse((nd + heuristic(&next), nd, next)));
+      }
+    }
+  }
+  res
+}
+
+/// Everything reachable from `start`, including `start`.
+pub fn flood_fill<N, I>(start: N, neighbors: impl FnMut(&N) -> I) -> AHashSet<N>
+where
+  N: Hash + Eq + Clone,
+  I: IntoIterator<Item = N>,
+{
+  flood_fill_in(AHashSet::new(), start, neighbors)
+}
+
+/// `flood_fill` adding to `seen`. `neighbors` is called once per node
+/// reached.
+pub fn flood_fill_in<N, S, I>(mut seen: S, start: N, mut neighbors: impl FnMut(&N) -> I) -> S
+where
+  N: Clone,
+  S: NodeSet<N>,
+  I: IntoIterator<Item = N>,
+{
+  let mut stack = vec![start];
+  while let Some(cur) = stack.pop() {
+    if !seen.insert(cur.clone()) {
+      continue;
+    }
+    stack.extend(neighbors(&cur).into_iter().filter(|n| !seen.contains(n)));
+  }
+  seen
+}
+
+/// Depth first branch and bound, maximizing over finished states.
+///
+/// `score` gives the value of a finished state and `None` for unfinished
+/// ones, which get expanded with `children`. A state is pruned unless
+/// `bound`, an upper bound on any score reachable from it, beats the best
+/// score so far (starting at `initial`). `children` pushes the states
+/// following a state onto the stack it is given.
+pub fn branch_and_bound<S, V>(
+  start: S,
+  initial: V,
+  mut children: impl FnMut(&S, &mut Vec<S>),
+  mut score: impl FnMut(&S) -> Option<V>,
+  mut bound: impl FnMut(&S) -> V,
+) -> V
+where
+  V: Ord + Copy,
+{
+  let mut best = initial;
+  let mut stack = vec![start];
+  while let Some(state) = stack.pop() {
+    if let Some(v) = score(&state) {
+      best = best.max(v);
+      continue;
+    }
+    if bound(&state) <= best {
+      continue;
+    }
+    children(&state, &mut stack);
+  }
+  best
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // 0 -1- 1 -1- 2 -1- 3, with a direct 0 -5- 3 edge and 4 unreachable.
+  fn weighted(n: &u32) -> Vec<(u32, u32)> {
+    match n {
+      0 => vec![(1, 1), (3, 5)],
+      1 => vec![(0, 1), (2, 1)],
+      2 => vec![(1, 1), (3, 1)],
+      3 => vec![(2, 1), (0, 5)],
+      _ => vec![],
+    }
+  }
+
+  #[test]
+  fn dijkstra_distances_and_paths() {
+    let res = dijkstra([0], weighted, |_| false);
+    assert_eq!([0, 1, 2, 3].map(|n| res.dist(&n)), [Some(0), Some(1), Some(2), Some(3)]);
+    assert_eq!(res.dist(&4), None);
+    assert_eq!(res.goal(), None);
+    assert_eq!(res.path_to(&3), Some(vec![0, 1, 2, 3]));
+    assert_eq!(res.path_to(&0), Some(vec![0]));
+    assert_eq!(res.path_to(&4), None);
+
+    let res = dijkstra([0], weighted, |&n| n == 2);
+    assert_eq!((res.goal(), res.goal_dist()), (Some(&2), Some(2)));
+    assert_eq!(res.goal_path(), Some(vec![0, 1, 2]));
+  }
+
+  #[test]
+  fn astar_grid() {
+    // 5x5 grid with a wall at x = 2 except at y = 4.
+    let open =
+      |&(x, y): &(i32, i32)| (0..5).contains(&x) && (0..5).contains(&y) && (x != 2 || y == 4);
+    let neighbors = |&(x, y): &(i32, i32)| {
+      [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)].into_iter().filter(open).map(|p| (p, 1))
+    };
+    let goal = (4, 0);
+    let manhattan = |&(x, y): &(i32, i32)| (goal.0 - x).abs() + (goal.1 - y).abs();
+    let res = astar([(0, 0)], neighbors, manhattan, |&p| p == goal);
+    assert_eq!(res.goal_dist(), Some(12));
+    let path = res.goal_path().unwrap();
+    assert_eq!((path.len(), path[0], path[12]), (13, (0, 0), goal));
+    assert!(path.contains(&(2, 4)));
+    assert_eq!(dijkstra([(0, 0)], neighbors, |&p| p == goal).goal_dist(), Some(12));
+  }
+
+  #[test]
+  fn bfs_dense_storage() {
+    let neighbors = |&n: &usize| [n + 1, n * 2].into_iter().filter(|&m| m < 20);
+    let hashed = bfs([1], neighbors, |&n| n == 19);
+    let dense = bfs_in(Dense::new(20, |&n: &usize| n), NoParents, [1], neighbors, |&n| n == 19);
+    assert_eq!(hashed.goal_dist(), Some(6));
+    assert_eq!(dense.goal_dist(), hashed.goal_dist());
+    assert_eq!(hashed.goal_path(), Some(vec![1, 2, 4, 8, 9, 18, 19]));
+    assert_eq!((0..20).map(|n| dense.dist(&n)).collect::<Vec<_>>()[..3], [None, Some(0), Some(1)]);
+  }
+
+  #[test]
+  fn flood_fill_stores() {
+    let neighbors = |&n: &u32| [n / 2, n * 3 % 17];
+    let hashed = flood_fill(5, neighbors);
+    let dense = flood_fill_in(Dense::<bool, _>::new(17, |&n: &u32| n as usize), 5, neighbors);
+    assert!((0..17).all(|n| hashed.contains(&n) == dense.contains(&n)));
+    assert!(hashed.contains(&5) && hashed.contains(&0));
+  }
+
+  #[test]
+  fn branch_and_bound_knapsack() {
+    // Items (weight, value), capacity 10: best is 4 + 6 weight for 19.
+    let items = [(5, 10), (4, 8), (6, 11), (3, 4)];
+    let best = branch_and_bound(
+      (0, 0, 0),
+      0,
+      |&(i, w, v), stack| {
+        stack.push((i + 1, w, v));
+        if w + items[i].0 <= 10 {
+          stack.push((i + 1, w + items[i].0, v + items[i].1));
+        }
+      },
+      |&(i, _, v)| (i == items.len()).then_some(v),
+      |&(i, _, v)| v + items[i..].iter().map(|it| it.1).sum::<i32>(),
+    );
+    assert_eq!(best, 19);
+  }
+}
@@ -1,9 +1,9 @@
 use std::fmt::Display;
+use std::io::Write;
 use std::time::Duration;
 
-use bench::aoc_run_batch;
-use bench_proc::aoc_bench_proc;
-use error::AocResult;
+use bench::{Answer, Status};
+use error::{aoc_error, AocResult};
 use itertools::Itertools;
 
 fn pretty_time(duration: Duration) -> String {
@@ -30,34 +30,47 @@ fn truncate<const M: usize, T: Clone + Default, const N: usize>(arr: [T; N]) ->
   dst
 }
 
-fn run_all() -> AocResult<()> {
-  let times = vec![
-    aoc_bench_proc!(day  1: 1000 runs, expected 71471 211189),
-    aoc_bench_proc!(day  2: 1000 runs, expected 10310 14859),
-    aoc_bench_proc!(day  3: 1000 runs, expected 7831 2683),
-    aoc_bench_proc!(day  4: 1000 runs, expected 464 770),
-    aoc_bench_proc!(day  5: 1000 runs, expected "VJSFHWGFT" "LCTQFBVZV"),
-    aoc_bench_proc!(day  6: 1000 runs, expected 1578 2178),
-    aoc_bench_proc!(day  7: 1000 runs, expected 1543140 1117448),
-    aoc_bench_proc!(day  8: 1000 runs, expected 1538 496125),
-    aoc_bench_proc!(day  9: 1000 runs, expected 6339 2541),
-    aoc_bench_proc!(day 10: 1000 runs), //expected 13860 "RZHFGJCB"),
-    aoc_bench_proc!(day 11: 1000 runs, expected 110220 19457438264_i64),
-    aoc_bench_proc!(day 12: 1000 runs, expected 456 454),
-    aoc_bench_proc!(day 13: 1000 runs, expected 6420 22000),
-    aoc_bench_proc!(day 14: 1000 runs, expected 838 27539),
-    aoc_bench_proc!(day 15: 1000 runs, expected 4907780 13639962836448_i64),
-    aoc_bench_proc!(day 16:   10 runs, expected 1737 2216),
-    aoc_bench_proc!(day 17: 1000 runs, expected 3071 1523615160362_i64),
-    aoc_bench_proc!(day 18: 1000 runs, expected 4450 2564),
-    aoc_bench_proc!(day 19:  100 runs, expected 1565 10672),
-    aoc_bench_proc!(day 20:  100 runs, expected 11616 9937909178485_i64),
-    aoc_bench_proc!(day 21: 1000 runs, expected 256997859093114.0 3952288690726_i64),
-    aoc_bench_proc!(day 22: 1000 runs, expected 30552 184106),
-    aoc_bench_proc!(day 23:   30 runs, expected 3987 938),
-    aoc_bench_proc!(day 24:   30 runs, expected 242 720),
-    aoc_bench_proc!(day 25: 1000 runs, expected "2-20=01--0=0=0=2-120" "Done!"),
-  ];
+fn make_row<const N: usize>(
+  prefix: &str,
+  parse_elapsed: Duration,
+  run_elapsed: Duration,
+  total: Duration,
+  max: Duration,
+) -> [String; N] {
+  let fraction = run_elapsed.as_secs_f32() / total.as_secs_f32();
+  let max_fraction = run_elapsed.as_secs_f32() / max.as_secs_f32();
+
+  let width = 20.0;
+  let x = (2.0 * width * max_fraction) as usize;
+  let bar = "─".repeat(x / 2) + if x % 2 == 1 { "╴" } else { "" };
+  truncate([
+    prefix.to_string(),
+    pretty_time(parse_elapsed),
+    pretty_time(run_elapsed),
+    format!("{:.2}%", 100.0 * fraction),
+    bar,
+  ])
+}
+
+/// Times every registered day, keeping only days carrying `tag` and dropping
+/// days carrying `skip_tag` when given.
+fn run_all(tag: Option<&str>, skip_tag: Option<&str>) -> AocResult<()> {
+  let mut times = vec![];
+  for solution in bench::solutions()? {
+    if tag.is_some_and(|t| !solution.tags.contains(&t))
+      || skip_tag.is_some_and(|t| solution.tags.contains(&t))
+    {
+      continue;
+    }
+    let day = format!("{:02}", solution.day);
+    println!("Timing day {} {} times...", day, solution.runs);
+    let timeout = solution.timeout();
+    let res = bench::in_child(&["__child", "bench", &solution.day.to_string()], timeout)?;
+    times.push((day, res, timeout));
+  }
+  if times.is_empty() {
+    return aoc_error("No days to bench");
+  }
 
   fn average(times: Vec<Duration>) -> Duration {
     let n_outliers = times.len() / 10;
@@ -73,46 +86,32 @@ fn run_all() -> AocResult<()> {
 
   let avg_times = times
     .into_iter()
-    .map(|(label, x)| (label, average(x.parse_elapsed), average(x.run_elapsed)))
+    .map(|(label, x, timeout)| {
+      (label, average(x.parse_elapsed), average(x.run_elapsed), (x.status, timeout))
+    })
     .collect_vec();
 
-  fn make_row<const N: usize>(
-    prefix: &str,
-    parse_elapsed: Duration,
-    run_elapsed: Duration,
-    total: Duration,
-    max: Duration,
-  ) -> [String; N] {
-    let fraction = run_elapsed.as_secs_f32() / total.as_secs_f32();
-    let max_fraction = run_elapsed.as_secs_f32() / max.as_secs_f32();
-
-    let width = 20.0;
-    let x = (2.0 * width * max_fraction) as usize;
-    let bar = "─".repeat(x / 2) + if x % 2 == 1 { "╴" } else { "" };
-    truncate([
-      prefix.to_string(),
-      pretty_time(parse_elapsed),
-      pretty_time(run_elapsed),
-      format!("{:.2}%", 100.0 * fraction),
-      bar,
-    ])
-  }
-
-  let run_max = avg_times.iter().map(|(_, _parse, run)| *run).max().unwrap();
-  let run_total = avg_times.iter().map(|(_, _parse, run)| run).sum();
-  let parse_total = avg_times.iter().map(|(_, parse, _run)| parse).sum();
+  let run_max = avg_times.iter().map(|(_, _parse, run, _)| *run).max().unwrap();
+  let run_total = avg_times.iter().map(|(_, _parse, run, _)| run).sum();
+  let parse_total = avg_times.iter().map(|(_, parse, _run, _)| parse).sum();
 
   let mut table = Table::new();
   use Row::*;
   table.push(Row::header("", "Parse", "Run"));
-  for (name, parse_elapsed, run_elapsed) in avg_times {
-    table.push(Data(make_row(
-      &name,
-      parse_elapsed,
-      run_elapsed,
-      run_total,
-      run_max,
-    )));
+  let mut failures = vec![];
+  for (name, parse_elapsed, run_elapsed, (status, timeout)) in avg_times {
+    let mut row = make_row(&name, parse_elapsed, run_elapsed, run_total, run_max);
+    let label = match &status {
+      Status::Ok => None,
+      Status::Timeout => Some("TIMEOUT"),
+      Status::Panic(_) => Some("PANIC"),
+      Status::Error(_) => Some("ERROR"),
+    };
+    if let Some(label) = label {
+      row[2] = label.to_string();
+      failures.push((name, status, timeout));
+    }
+    table.push(Data(row));
   }
   table.push(Summary(make_row(
     "Sum",
@@ -123,6 +122,18 @@ fn run_all() -> AocResult<()> {
   )));
 
   println!("\n{table}\n");
+  let n_failed = failures.len();
+  for (name, status, timeout) in failures {
+    match status {
+      Status::Timeout => println!("Day {}: timed out after {:?}", name, timeout),
+      Status::Panic(msg) => println!("Day {}: panicked: {}", name, msg),
+      Status::Error(msg) => println!("Day {}: {}", name, msg),
+      Status::Ok => {}
+    }
+  }
+  if n_failed > 0 {
+    return aoc_error(&format!("{} day(s) failed", n_failed));
+  }
   Ok(())
 }
 
@@ -175,29 +186,389 @@ impl Display for Table {
       " ".repeat(w0 + 1) + chars[0] + &chars[1].repeat(4 + w1 + w2 + w3) + chars[2]
     }
     let header = self.data.first().unwrap();
-    let summary = self.data.last().unwrap();
-    let data = &self.data[1..self.data.len() - 1];
-    let widths = [3, 9, 9, 7];
+    let (data, summary) = match self.data.last() {
+      Some(summary @ Row::Summary(_)) => (&self.data[1..self.data.len() - 1], Some(summary)),
+      _ => (&self.data[1..], None),
+    };
+    let prefix_width = data
+      .iter()
+      .map(|row| match row {
+        Row::Data([prefix, ..]) => prefix.chars().count(),
+        _ => 0,
+      })
+      .max()
+      .unwrap_or(0);
+    let widths = [prefix_width.max(3), 9, 9, 7];
     let mut lines = vec![];
     lines.push(fmt(header, &widths));
     lines.push(line(["╭", "─", "╮"], &widths));
     lines.extend(data.iter().map(|s| fmt(s, &widths)));
-    lines.push(line(["├", "─", "┤"], &widths));
-    lines.push(fmt(summary, &widths));
+    if let Some(summary) = summary {
+      lines.push(line(["├", "─", "┤"], &widths));
+      lines.push(fmt(summary, &widths));
+    }
     lines.push(line(["╰", "─", "╯"], &widths));
     write!(f, "{}", lines.join("\n"))
   }
 }
 
+/// Runs a registered day `n_runs` times, keeping the last results.
+fn run_day(day: u32, fname: &str, n_runs: usize) -> AocResult<bench::RunResult> {
+  let Some(solution) = bench::solution(day)? else {
+    return aoc_error(&format!("Day {} is not registered", day));
+  };
+  let mut parse_elapsed = vec![];
+  let mut run_elapsed = vec![];
+  for _ in 1..n_runs {
+    let res = (solution.run)(fname)?;
+    parse_elapsed.extend(res.parse_elapsed);
+    run_elapsed.extend(res.run_elapsed);
+  }
+  let mut res = (solution.run)(fname)?;
+  parse_elapsed.append(&mut res.parse_elapsed);
+  run_elapsed.append(&mut res.run_elapsed);
+  Ok(bench::RunResult { parse_elapsed, run_elapsed, ..res })
+}
+
+/// Runs a registered implementation `n_runs` times, keeping the last result.
+fn run_impl(
+  imp: &bench::Implementation,
+  fname: &str,
+  n_runs: usize,
+) -> AocResult<bench::PartResult> {
+  let mut parse_elapsed = vec![];
+  let mut run_elapsed = vec![];
+  for _ in 1..n_runs {
+    let res = (imp.run)(fname)?;
+    parse_elapsed.extend(res.parse_elapsed);
+    run_elapsed.extend(res.run_elapsed);
+  }
+  let mut res = (imp.run)(fname)?;
+  parse_elapsed.append(&mut res.parse_elapsed);
+  run_elapsed.append(&mut res.run_elapsed);
+  Ok(bench::PartResult { parse_elapsed, run_elapsed, ..res })
+}
+
+fn mean(times: &[Duration]) -> Duration { times.iter().sum::<Duration>() / times.len() as u32 }
+
+/// Runs a day with the implementation `name` for the parts that have one, and
+/// the registered solution for the others.
+fn run_named(day: u32, name: &str, fname: &str, n_runs: usize) -> AocResult<()> {
+  let all = bench::implementations(day)?;
+  let imps = all.iter().filter(|i| i.name == name).collect_vec();
+  if imps.is_empty() {
+    let names = all.iter().map(|i| i.name).unique().join(", ");
+    return aoc_error(&format!(
+      "Day {} has no implementation `{}` (available: {})",
+      day, name, names
+    ));
+  }
+  let default = if imps.len() < 2 { Some(run_day(day, fname, n_runs)?) } else { None };
+  for part in 1..=2 {
+    if let Some(imp) = imps.iter().find(|i| i.part == part) {
+      let res = run_impl(imp, fname, n_runs)?;
+      let elapsed = pretty_time(mean(&res.run_elapsed));
+      println!("Part {} [{}]: {} ({})", part, name, res.result, elapsed);
+    } else if let Some(default) = &default {
+      let res = if part == 1 { &default.part1_result } else { &default.part2_result };
+      println!("Part {} [default]: {}", part, res);
+    }
+  }
+  Ok(())
+}
+
+/// Runs every implementation of the given days side by side, checking each
+/// answer against the registered solution. Returns whether all agree.
+fn cross_check(days: &[u32], fname: impl Fn(u32) -> String, n_runs: usize) -> AocResult<bool> {
+  let mut rows = vec![];
+  let mut mismatches = vec![];
+  for &day in days {
+    let imps = bench::implementations(day)?;
+    if imps.is_empty() {
+      continue;
+    }
+    let fname = fname(day);
+    let reference = run_day(day, &fname, 1)?;
+    for imp in imps {
+      let res = run_impl(imp, &fname, n_runs)?;
+      let expected = if imp.part == 1 { &reference.part1_result } else { &reference.part2_result };
+      let (expected, got) = (Answer::of(expected), Answer::of(&res.result));
+      if !expected.matches(&got) {
+        mismatches.push(format!(
+          "Day {:02} part {} [{}]: expected {}, got {}",
+          day, imp.part, imp.name, expected, got
+        ));
+      }
+      let label = format!("{:02}.{} {}", day, imp.part, imp.name);
+      rows.push((label, mean(&res.parse_elapsed), mean(&res.run_elapsed)));
+    }
+  }
+  if rows.is_empty() {
+    println!("No alternative implementations registered");
+    return Ok(true);
+  }
+
+  let run_max = rows.iter().map(|(_, _, run)| *run).max().unwrap();
+  let run_total = rows.iter().map(|(_, _, run)| run).sum();
+  let mut table = Table::new();
+  table.push(Row::header("", "Parse", "Run"));
+  for (label, parse_elapsed, run_elapsed) in rows {
+    table.push(Row::Data(make_row(&label, parse_elapsed, run_elapsed, run_total, run_max)));
+  }
+  println!("\n{table}\n");
+  for mismatch in &mismatches {
+    println!("{}", mismatch);
+  }
+  Ok(mismatches.is_empty())
+}
+
+fn input_path(day: u32) -> String { format!("inputs/{:02}input", day) }
+
+/// Parses a command line argument, naming it in the error.
+fn parse_arg<T: std::str::FromStr>(arg: &str, what: &str) -> AocResult<T> {
+  match arg.parse() {
+    Ok(x) => Ok(x),
+    Err(_) => aoc_error(&format!("Expected {}, got {:?}", what, arg)),
+  }
+}
+
+/// Runs a day on the example in `fname`, in the child started by `run_sample`.
+fn solve_sample(day: u32, fname: &str) -> AocResult<bench::RunResult> {
+  let Some(run) = solutions::samples::sample(day).and_then(|s| s.run) else {
+    return run_day(day, fname, 1);
+  };
+  let t = std::time::Instant::now();
+  let (part1, part2) = run(fname)?;
+  Ok(bench::RunResult {
+    parse_elapsed: vec![Duration::ZERO],
+    run_elapsed: vec![t.elapsed()],
+    part1_result: Box::new(part1),
+    part2_result: Box::new(part2),
+    status: Status::Ok,
+  })
+}
+
+/// Runs a day on its built in example, returning whether both answers match.
+fn run_sample(day: u32) -> AocResult<bool> {
+  let Some(sample) = solutions::samples::sample(day) else {
+    println!("Day {:02}: no sample", day);
+    return Ok(true);
+  };
+  // Days read their input from a file, one per process so parallel runs
+  // don't clobber each other.
+  let path = std::env::temp_dir().join(format!("aoc_sample_{}_{:02}", std::process::id(), day));
+  std::fs::write(&path, sample.input)?;
+  let fname = path.to_string_lossy().into_owned();
+
+  let timeout = bench::solution(day)?.map_or_else(bench::default_timeout, |s| s.timeout());
+  let res = bench::in_child(&["__child", "sample", &day.to_string(), &fname], timeout);
+  std::fs::remove_file(&path)?;
+  let res = res?;
+  if res.status != Status::Ok {
+    println!("Day {:02}: {:?}", day, res.status);
+    return Ok(false);
+  }
+
+  let mut ok = true;
+  let mut report = vec![];
+  for (part, expected, got) in
+    [(1, sample.part1, &res.part1_result), (2, sample.part2, &res.part2_result)]
+  {
+    let (expected, got) = (Answer::parse(expected), Answer::of(got));
+    if expected.matches(&got) {
+      report.push(format!("{}", got));
+    } else {
+      ok = false;
+      report.push(format!("part {} expected {}, got {}", part, expected, got));
+    }
+  }
+  let status = if ok { "ok" } else { "FAIL" };
+  println!("Day {:02}: {:4} {}", day, status, report.join(" | "));
+  Ok(ok)
+}
+
 fn main() -> AocResult<()> {
-  let args: Vec<String> = std::env::args().collect();
+  let mut args: Vec<String> = std::env::args().collect();
+  let sample = args.iter().any(|a| a == "--sample");
+  let stream = args.iter().any(|a| a == "--stream");
+  let impls = args.iter().any(|a| a == "--impls");
+  let trace = args.iter().any(|a| a == "--trace");
+  let json = args.iter().any(|a| a == "--json");
+  args.retain(|a| {
+    !["--sample", "--stream", "--impls", "--trace", "--json"].contains(&a.as_str())
+  });
+  let imp = match args.iter().position(|a| a == "--impl") {
+    None => None,
+    Some(i) => {
+      let Some(name) = args.get(i + 1).cloned() else {
+        return aoc_error("--impl needs an implementation name");
+      };
+      args.drain(i..i + 2);
+      Some(name)
+    }
+  };
+  if args.len() < 2 {
+    return aoc_error(
+      "Usage: aoc DAY|all [RUNS] [INPUT] or aoc bench, \
+       with --sample, --impl NAME, --impls, --stream or --trace for the other modes",
+    );
+  }
 
-  let n_runs = args.get(2).map(|s| s.parse().unwrap()).unwrap_or(1);
+  let n_runs = || -> AocResult<usize> {
+    let n = args.get(2).map_or(Ok(1), |s| parse_arg(s, "a number of runs"))?;
+    if n == 0 {
+      return aoc_error("Expected at least one run");
+    }
+    Ok(n)
+  };
+  let day = || parse_arg::<u32>(&args[1], "a day number or `all`");
 
-  if args[1].starts_with("bench") {
-    run_all()?;
+  if args[1] == "__child" {
+    // Started by `bench::in_child`: aoc __child bench DAY | aoc __child sample DAY INPUT
+    // Bad arguments are reported back to the parent like any other error.
+    bench::child_main(|| {
+      let usage = "Usage: aoc __child bench DAY | aoc __child sample DAY INPUT";
+      let Some(day) = args.get(3) else {
+        return aoc_error(usage);
+      };
+      let day = parse_arg::<u32>(day, "a day number")?;
+      match (args[2].as_str(), args.get(4)) {
+        ("bench", None) => match bench::solution(day)? {
+          Some(solution) => (solution.bench)(),
+          None => aoc_error(&format!("Day {} is not registered", day)),
+        },
+        ("sample", Some(fname)) => solve_sample(day, fname),
+        _ => aoc_error(usage),
+      }
+    });
+  } else if args[1].starts_with("bench") {
+    // aoc bench [--timeout SECS] [--tag TAG] [--skip-tag TAG]
+    // --timeout applies to days without a `timeout` of their own.
+    if let Some(i) = args.iter().position(|a| a == "--timeout") {
+      let timeout = args
+        .get(i + 1)
+        .and_then(|s| s.parse().ok())
+        .filter(|&secs: &f64| secs > 0.0)
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+      let Some(timeout) = timeout else {
+        return aoc_error("--timeout needs a positive number of seconds");
+      };
+      bench::set_default_timeout(timeout);
+    }
+    let flag = |name: &str| -> AocResult<Option<&str>> {
+      match args.iter().position(|a| a == name) {
+        None => Ok(None),
+        Some(i) => match args.get(i + 1) {
+          Some(t) => Ok(Some(t.as_str())),
+          None => aoc_error(&format!("{} needs a tag", name)),
+        },
+      }
+    };
+    run_all(flag("--tag")?, flag("--skip-tag")?)?;
+  } else if stream {
+    // aoc 1 --stream [INPUT|-] [K]
+    if args[1] != "1" {
+      return aoc_error("--stream is only supported for day 1");
+    }
+    let fname = args.get(2).cloned().unwrap_or_else(|| input_path(1));
+    let k = args.get(3).map_or(Ok(3), |s| parse_arg(s, "the number of elves to show"))?;
+    let t = std::time::Instant::now();
+    let (top, bytes) = if fname == "-" {
+      solutions::day01::stream_top_k(std::io::stdin().lock(), k)?
+    } else {
+      let f = std::fs::File::open(&fname)?;
+      solutions::day01::stream_top_k(std::io::BufReader::with_capacity(1 << 20, f), k)?
+    };
+    let elapsed = t.elapsed();
+    for (rank, (elf, calories)) in top.iter().enumerate() {
+      println!("#{}: elf {} carries {}", rank + 1, elf, calories);
+    }
+    println!("Top {} total: {}", k, top.iter().map(|&(_, c)| c as i64).sum::<i64>());
+    let mb = bytes as f64 / 1e6;
+    println!(
+      "Read {:.1} MB in {} ({:.1} MB/s)",
+      mb,
+      pretty_time(elapsed),
+      mb / elapsed.as_secs_f64()
+    );
+  } else if sample {
+    // aoc DAY|all --sample
+    let days = if args[1] == "all" {
+      bench::solutions()?.iter().map(|s| s.day).collect_vec()
+    } else {
+      vec![day()?]
+    };
+    let mut failed = 0;
+    for day in days {
+      if !run_sample(day)? {
+        failed += 1;
+      }
+    }
+    if failed > 0 {
+      return aoc_error(&format!("{} sample(s) failed", failed));
+    }
+  } else if trace {
+    // aoc 5 --trace [--json] [9000|9001] [INPUT]
+    use solutions::day05::{Crane, TraceFormat};
+    if args[1] != "5" {
+      return aoc_error("--trace is only supported for day 5");
+    }
+    let mut rest = &args[2..];
+    let cranes = match rest.first().map(|s| s.as_str()) {
+      Some("9000") => vec![Crane::CrateMover9000],
+      Some("9001") => vec![Crane::CrateMover9001],
+      _ => vec![Crane::CrateMover9000, Crane::CrateMover9001],
+    };
+    if cranes.len() == 1 {
+      rest = &rest[1..];
+    }
+    let fname = rest.first().cloned().unwrap_or_else(|| input_path(5));
+    let format = if json { TraceFormat::Json } else { TraceFormat::Text };
+    let input = solutions::day05::parse_input(&fname)?;
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    for crane in cranes {
+      if !json {
+        writeln!(out, "== {:?} ==\n", crane)?;
+      }
+      solutions::day05::trace(&input, crane, format, &mut out)?;
+    }
+  } else if impls {
+    // aoc DAY|all --impls [RUNS] [INPUT]
+    let (days, fname): (_, Box<dyn Fn(u32) -> String>) = if args[1] == "all" {
+      let days = bench::solutions()?
+        .iter()
+        .map(|s| s.day)
+        .filter(|&day| std::path::Path::new(&input_path(day)).exists())
+        .collect_vec();
+      (days, Box::new(input_path))
+    } else {
+      let day = day()?;
+      let fname = args.get(3).cloned();
+      (vec![day], Box::new(move |day| fname.clone().unwrap_or_else(|| input_path(day))))
+    };
+    if !cross_check(&days, fname, n_runs()?)? {
+      return aoc_error("Implementations disagree");
+    }
+  } else if let Some(name) = imp {
+    // aoc DAY --impl NAME [RUNS] [INPUT]
+    let day = day()?;
+    let fname = args.get(3).cloned().unwrap_or_else(|| input_path(day));
+    run_named(day, &name, &fname, n_runs()?)?;
+  } else if args[1] == "all" {
+    for solution in bench::solutions()? {
+      let fname = input_path(solution.day);
+      if !std::path::Path::new(&fname).exists() {
+        println!("Day {:02}: no input", solution.day);
+        continue;
+      }
+      let res = run_day(solution.day, &fname, n_runs()?)?;
+      println!("Day {:02}: {} | {}", solution.day, res.part1_result, res.part2_result);
+    }
   } else {
-    let res = aoc_run_batch!(solutions::day17, args[1], n_runs as u32);
+    // aoc DAY [RUNS] [INPUT]
+    let day = day()?;
+    let fname = args.get(3).cloned().unwrap_or_else(|| input_path(day));
+    let res = run_day(day, &fname, n_runs()?)?;
     println!("Part 1: {}", res.part1_result);
     println!("Part 2: {}", res.part2_result);
     println!("Elapsed: {}", pretty_time(res.avg_elapsed()));